@@ -3,10 +3,17 @@ import {execFile} from 'node:child_process'
 import {join, resolve} from 'node:path'
 import {promisify} from 'node:util'
 
-import {BaseCommand, type JsonOutput} from '../lib/base-command.js'
+import {
+  BaseCommand,
+  failOnFlag,
+  formatFlag,
+  type JsonOutput,
+  notifyDryRunFlag,
+  notifyFlag,
+} from '../lib/base-command.js'
 import {loadConfig} from '../lib/configuration/loader.js'
 import {parseDiff, type RefPair} from '../lib/diff/parser.js'
-import {getGitDiff, getGitToplevel, getWorkingTreeStatus, isValidRef} from '../lib/git/index.js'
+import {getGitDiff, getGitToplevel, getRemotes, getWorkingTreeStatus, isValidRef} from '../lib/git/index.js'
 import {processFiles} from '../lib/processing/runner.js'
 import {type ContentProvider} from '../lib/processing/types.js'
 
@@ -35,16 +42,23 @@ When no arguments are provided, defaults are chosen based on the working tree st
 - If there are unstaged changes, compares HEAD to the working directory
 - If there are only staged changes, use --staged to check them
 
-When using --json, a "lineRange" field is included. Note that this range refers to the line numbers within the *filtered artifact* (the code snippet shown in the report), NOT the original source file.`
+When using --json, a "lineRange" field is included. Note that this range refers to the line numbers within the *filtered artifact* (the code snippet shown in the report), NOT the original source file. The "locations" field lists where the changed matches are in the old and new source files.`
   static override examples = [
     '<%= config.bin %> <%= command.id %>                  # auto-detect changes',
     '<%= config.bin %> <%= command.id %> --staged         # check staged changes only',
+    '<%= config.bin %> <%= command.id %> --staged --fail-on error  # block commits with error findings',
     '<%= config.bin %> <%= command.id %> HEAD~1 HEAD',
     '<%= config.bin %> <%= command.id %> main feat/foo',
     '<%= config.bin %> <%= command.id %> HEAD .           # compare HEAD to working directory',
     '<%= config.bin %> <%= command.id %> main HEAD --repo ../other-project',
+    '<%= config.bin %> <%= command.id %> main HEAD --format sarif > distill.sarif',
+    '<%= config.bin %> <%= command.id %> main HEAD --notify-dry-run  # list who would be notified',
   ]
   static override flags = {
+    'fail-on': failOnFlag,
+    format: formatFlag,
+    notify: notifyFlag,
+    'notify-dry-run': notifyDryRunFlag,
     repo: Flags.string({
       char: 'r',
       defaultHelp: 'Find the closest top-level git repo to the current directory',
@@ -68,7 +82,7 @@ When using --json, a "lineRange" field is included. Note that this range refers
 
     // No changes detected - exit gracefully
     if (!resolved) {
-      return this.jsonEnabled() ? {reports: []} : undefined
+      return this.isStructuredOutput() ? this.outputReports({reports: []}) : undefined
     }
 
     const {base, diffOptions, head} = resolved
@@ -85,8 +99,8 @@ When using --json, a "lineRange" field is included. Note that this range refers
     // Generate diff using git
     const diffText = await getGitDiff(base, head, repoPath, diffOptions)
     if (!diffText.trim()) {
-      if (this.jsonEnabled()) {
-        return {reports: []}
+      if (this.isStructuredOutput()) {
+        return this.outputReports({reports: []})
       }
 
       this.log('No changes between %s and %s', base, head)
@@ -96,8 +110,8 @@ When using --json, a "lineRange" field is included. Note that this range refers
     // Parse the diff
     const {files} = parseDiff(diffText)
     if (files.length === 0) {
-      if (this.jsonEnabled()) {
-        return {reports: []}
+      if (this.isStructuredOutput()) {
+        return this.outputReports({reports: []})
       }
 
       this.log('No files found in diff')
@@ -125,9 +139,22 @@ When using --json, a "lineRange" field is included. Note that this range refers
       refs,
     })
 
+    const repository = await this.getRepositoryName(repoPath)
+    await this.notify(result.reports, {refs, ...(repository ? {repository} : {})}, config.notifiers)
+
     return this.outputReports(result)
   }
 
+  /**
+   * Name the repository `owner/repo` after its GitHub remote, preferring `origin`, for notifications.
+   * Local repositories without a GitHub remote have no name.
+   */
+  private async getRepositoryName(repoPath: string): Promise<string | undefined> {
+    const remotes = (await getRemotes(repoPath)).filter((remote) => remote.owner && remote.repo)
+    const remote = remotes.find((candidate) => candidate.name === 'origin') ?? remotes[0]
+    return remote ? `${remote.owner}/${remote.repo}` : undefined
+  }
+
   /**
    * Resolve base and head refs using smart defaults based on working tree state.
    * Returns null if no changes are detected (caller should exit gracefully).
@@ -2,12 +2,32 @@ import {Args, Flags} from '@oclif/core'
 import {resolve} from 'node:path'
 import {Octokit} from 'octokit'
 
-import {BaseCommand, type JsonOutput} from '../lib/base-command.js'
+import {
+  BaseCommand,
+  failOnFlag,
+  formatFlag,
+  type JsonOutput,
+  notifyDryRunFlag,
+  notifyFlag,
+} from '../lib/base-command.js'
 import {loadConfig} from '../lib/configuration/loader.js'
 import {parseDiff} from '../lib/diff/parser.js'
 import {getCurrentBranch, getRemotes, getTrackingBranch, isInsideGitRepo} from '../lib/git/index.js'
+import {
+  clearReportComment,
+  contactStakeholders,
+  describeContactPlan,
+  planContacts,
+  postReview,
+  publishCheckRun,
+  type PullRequestRef,
+  renderReportComment,
+  sendGithubNotifications,
+  upsertReportComment,
+} from '../lib/github/index.js'
 import {processFiles} from '../lib/processing/runner.js'
 import {type ContentProvider} from '../lib/processing/types.js'
+import {type ReportOutput} from '../lib/reports/index.js'
 
 interface PrInfo {
   number: number
@@ -32,12 +52,50 @@ Requires GITHUB_TOKEN environment variable for authentication.`
     '<%= config.bin %> <%= command.id %> 123                            # PR number (uses detected remote)',
     '<%= config.bin %> <%= command.id %> https://github.com/owner/repo/pull/123',
     '<%= config.bin %> <%= command.id %> 123 --repo owner/repo',
+    '<%= config.bin %> <%= command.id %> 123 --format sarif > distill.sarif',
+    '<%= config.bin %> <%= command.id %> 123 --comment                  # post or update a summary comment',
+    '<%= config.bin %> <%= command.id %> 123 --review                   # comment inline on changed lines',
+    '<%= config.bin %> <%= command.id %> 123 --notify-dry-run           # show who would be contacted',
+    '<%= config.bin %> <%= command.id %> 123 --check-run                # annotate the head commit with a check run',
+    '<%= config.bin %> <%= command.id %> 123 --notify                   # deliver notify targets, e.g. from CI',
   ]
   static override flags = {
+    'check-conclusion': Flags.option({
+      default: 'neutral',
+      dependsOn: ['check-run'],
+      description: 'Conclusion of the check run when a warning or error signal fires',
+      options: ['failure', 'neutral'] as const,
+    })(),
+    'check-run': Flags.boolean({
+      default: false,
+      description: 'Report the findings as a check run with line annotations on the PR head commit',
+    }),
+    comment: Flags.boolean({
+      default: false,
+      description: 'Post the reports as a single PR comment, updated in place on later runs',
+    }),
+    'contact-stakeholders': Flags.boolean({
+      default: false,
+      description: 'Request reviews from or mention stakeholders of concerns whose signals fired, per their contactMethod',
+    }),
+    'fail-on': failOnFlag,
+    format: formatFlag,
+    notify: notifyFlag,
+    'notify-dry-run': notifyDryRunFlag,
     repo: Flags.string({
       char: 'r',
       description: 'GitHub repository (owner/repo). Required if not running in a git repo.',
     }),
+    review: Flags.boolean({
+      default: false,
+      description: 'Post a PR review with an inline comment on the changed lines of each finding',
+    }),
+    'stale-comment': Flags.option({
+      default: 'delete',
+      dependsOn: ['comment'],
+      description: 'What to do with the PR comment from an earlier run when no signals fire',
+      options: ['collapse', 'delete'] as const,
+    })(),
   }
 
   public async run(): Promise<JsonOutput | void> {
@@ -54,7 +112,7 @@ Requires GITHUB_TOKEN environment variable for authentication.`
     const {number, owner, repo} = prInfo
 
     // Log the remote being used if it was auto-detected
-    if (prInfo.remoteUrl) {
+    if (prInfo.remoteUrl && !this.isStructuredOutput()) {
       this.log(`Using remote ${prInfo.remoteUrl}`)
     }
 
@@ -97,8 +155,16 @@ Requires GITHUB_TOKEN environment variable for authentication.`
     const diffString = diffText as unknown as string
 
     if (!diffString.trim()) {
-      if (this.jsonEnabled()) {
-        return {reports: []}
+      if (this.flags['check-run']) {
+        await this.publishCheckRun(octokit, {owner, repo}, pr.head.sha, [])
+      }
+
+      if (this.flags.comment) {
+        await this.clearComment(octokit, {number, owner, repo}, pr.head.sha)
+      }
+
+      if (this.isStructuredOutput()) {
+        return this.outputReports({reports: []})
       }
 
       this.log('No changes found in PR #%d', number)
@@ -133,9 +199,84 @@ Requires GITHUB_TOKEN environment variable for authentication.`
       refs,
     })
 
+    const contactOptions = {
+      author: pr.user?.login,
+      requestedReviewers: pr.requested_reviewers?.map((user) => user.login) ?? [],
+      requestedTeams: pr.requested_teams?.map((team) => team.slug) ?? [],
+    }
+
+    // Stakeholders of fired concerns are contacted with --contact-stakeholders, notify.github targets with --notify
+    const stakeholders = this.flags['contact-stakeholders']
+      ? result.reports.flatMap((report) => report.metadata?.stakeholders ?? [])
+      : []
+    if (stakeholders.length > 0) {
+      const plan = planContacts(stakeholders, contactOptions)
+      for (const stakeholder of plan.unreachable) {
+        this.warn(`Stakeholder '${stakeholder.name}' has no github handle, so they cannot be contacted`)
+      }
+
+      if (this.flags['notify-dry-run']) {
+        for (const line of describeContactPlan(plan)) {
+          this.logAside(line)
+        }
+      } else {
+        this.debug('contacting stakeholders', plan)
+        await contactStakeholders(octokit, {number, owner, repo}, plan)
+        // Don't ask notify.github targets that were just requested again
+        contactOptions.requestedReviewers.push(...plan.reviewers)
+        contactOptions.requestedTeams.push(...plan.teamReviewers)
+      }
+    }
+
+    if (this.flags.review && result.reports.length > 0) {
+      const plan = await postReview(octokit, {number, owner, repo}, pr.head.sha, result.reports, files)
+      this.debug('posted review', plan)
+    }
+
+    if (this.flags['check-run']) {
+      await this.publishCheckRun(octokit, {owner, repo}, pr.head.sha, result.reports)
+    }
+
+    await this.notify(
+      result.reports,
+      {pullRequest: {number, url: pr.html_url}, refs, repository: `${owner}/${repo}`},
+      config.notifiers,
+      (targets) => sendGithubNotifications(octokit, {number, owner, repo}, targets, contactOptions),
+    )
+
+    if (this.flags.comment) {
+      if (result.reports.length > 0) {
+        const outcome = await upsertReportComment(octokit, {number, owner, repo}, renderReportComment(result.reports))
+        this.debug('report comment', outcome)
+      } else {
+        await this.clearComment(octokit, {number, owner, repo}, pr.head.sha)
+      }
+    }
+
     return this.outputReports(result)
   }
 
+  /**
+   * Delete or collapse the comment from an earlier run, since no signals fire anymore.
+   */
+  private async clearComment(octokit: Octokit, pullRequest: PullRequestRef, headSha: string): Promise<void> {
+    const outcome = await clearReportComment(octokit, pullRequest, this.flags['stale-comment'], headSha)
+    this.debug('report comment', outcome)
+  }
+
+  /**
+   * Publish the findings as a check run, which succeeds when nothing fired.
+   */
+  private async publishCheckRun(
+    octokit: Octokit,
+    repository: {owner: string; repo: string},
+    headSha: string,
+    reports: ReportOutput[],
+  ): Promise<void> {
+    const outcome = await publishCheckRun(octokit, repository, headSha, reports, this.flags['check-conclusion'])
+    this.debug('check run', outcome)
+  }
+
   private async detectPrFromContext(octokit: Octokit): Promise<PrInfo> {
     const cwd = process.cwd()
 
@@ -0,0 +1,74 @@
+import {readFile} from 'node:fs/promises'
+import {join, resolve} from 'node:path'
+
+import {BaseCommand} from '../lib/base-command.js'
+import {type ConfigIssue, formatIssues, validateConfigSource} from '../lib/configuration/validator.js'
+import {getGitToplevel, isInsideGitRepo} from '../lib/git/index.js'
+import {compileRules} from '../lib/processing/compile.js'
+
+/** JSON output structure for the validate command */
+export interface ValidateJsonOutput {
+  config: string
+  issues: ConfigIssue[]
+  valid: boolean
+}
+
+export default class ValidateCommand extends BaseCommand<typeof ValidateCommand> {
+  static override description = `Check a distill configuration file for errors.
+
+Validates the file against the configuration schema, resolves every "use" reference, and compiles every regex, tree-sitter query, XPath expression, jq program, ast-grep pattern and Handlebars template.
+
+Exits with a non-zero status if any problem is found, so it can gate changes to distill.yml in CI.`
+  static override examples = [
+    '<%= config.bin %> <%= command.id %>                        # validate distill.yml in the repo root',
+    '<%= config.bin %> <%= command.id %> --config ci/distill.yml',
+    '<%= config.bin %> <%= command.id %> --json',
+  ]
+
+  public async run(): Promise<ValidateJsonOutput | void> {
+    const configPath = await this.resolveConfigPath()
+    this.debug('found config path', configPath)
+
+    let content: string
+    try {
+      content = await readFile(configPath, 'utf8')
+    } catch {
+      this.error(`Cannot read configuration file: ${configPath}`)
+    }
+
+    // Structural problems make compiling unreliable, so only compile a well-formed config
+    const {config, issueAt, issues} = validateConfigSource(content)
+    if (issues.length === 0) {
+      issues.push(...(await compileRules(config, issueAt)))
+    }
+
+    const valid = issues.length === 0
+    if (!valid) {
+      process.exitCode = 1
+    }
+
+    if (this.jsonEnabled()) {
+      return {config: configPath, issues, valid}
+    }
+
+    if (valid) {
+      this.log('%s is valid', configPath)
+      return
+    }
+
+    this.log(formatIssues(issues, configPath))
+  }
+
+  /**
+   * Use --config if given, otherwise distill.yml in the repo root (or the current directory outside a repo).
+   */
+  private async resolveConfigPath(): Promise<string> {
+    const cwd = process.cwd()
+    if (this.flags.config) {
+      return resolve(cwd, this.flags.config)
+    }
+
+    const root = (await isInsideGitRepo(cwd)) ? await getGitToplevel(cwd) : cwd
+    return join(root, 'distill.yml')
+  }
+}
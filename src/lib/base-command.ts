@@ -1,7 +1,14 @@
-import {Command, Flags, Interfaces} from '@oclif/core'
+import {Command, Flags, Interfaces, ux} from '@oclif/core'
 
+import type {NotifiersConfig, Severity} from '../lib/configuration/config.js'
+import type {SignalError} from '../lib/processing/runner.js'
 import type {ReportMetadata, ReportOutput} from '../lib/reports/index.js'
 
+import {type GithubNotifier, type NotifySource, planNotifications, sendNotifications} from '../lib/notify/index.js'
+import {getReportFingerprint} from '../lib/reports/index.js'
+import {createSarifLog} from '../lib/reports/sarif.js'
+import {meetsSeverity} from '../lib/reports/severity.js'
+
 // Type helpers for inherited flags and args
 export type InferredFlags<T extends typeof Command> = Interfaces.InferredFlags<
   (typeof BaseCommand)['baseFlags'] & T['flags']
@@ -13,6 +20,53 @@ export interface JsonOutput {
   reports: ReportMetadata[]
 }
 
+/**
+ * Output formats for commands that produce reports.
+ */
+export type OutputFormat = 'sarif' | 'text'
+
+/**
+ * `--format` flag for commands that produce reports.
+ */
+export const formatFlag = Flags.option({
+  default: 'text',
+  description: 'Output format. "sarif" writes a SARIF 2.1.0 log for code scanning dashboards and IDE viewers.',
+  exclusive: ['json'],
+  options: ['sarif', 'text'] as const,
+})()
+
+/**
+ * `--fail-on` flag for commands that produce reports.
+ */
+export const failOnFlag = Flags.option({
+  description: 'Exit with status 1 when any report has this severity or higher',
+  options: ['error', 'info', 'warning'] as const,
+})()
+
+/**
+ * `--notify` flag for commands that deliver notify targets.
+ * Off unless passed or set in `DISTILL_NOTIFY`, so running distill locally never notifies anyone by accident.
+ */
+export const notifyFlag = Flags.boolean({
+  allowNo: true,
+  default: false,
+  description: 'Deliver the notify targets of fired signals. Off by default, so local runs never notify anyone.',
+  env: 'DISTILL_NOTIFY',
+})
+
+/**
+ * `--notify-dry-run` flag for commands that deliver notify targets.
+ * `--dry-run` is its deprecated alias from before `distill pr` had notify targets.
+ */
+export const notifyDryRunFlag = Flags.boolean({
+  aliases: ['dry-run'],
+  default: false,
+  deprecateAliases: true,
+  description:
+    'Print who would be contacted instead of contacting them, with the delivery plan of notify targets as JSON: ' +
+    'each channel and target with the ids of the reports it would receive',
+})
+
 /**
  * Base command for distill CLI.
  * Provides shared flags and JSON output handling.
@@ -27,6 +81,7 @@ export interface JsonOutput {
  *
  * - **No results** (empty diff, clean working tree, no PR found): Return gracefully.
  *   - In JSON mode: return `[]` (empty array)
+ *   - In SARIF mode: output a log with no results, so dashboards clear earlier findings
  *   - In text mode: log a message with `this.log()` and return `undefined`
  *   This allows programmatic usage and testing without throwing.
  *
@@ -59,31 +114,134 @@ export abstract class BaseCommand<T extends typeof Command> extends Command {
     this.args = args as InferredArgs<T>
   }
 
+  /**
+   * Whether stdout is reserved for machine-readable output (`--json` or `--format sarif`).
+   * Informational messages should be skipped, and empty results still output.
+   */
+  protected isStructuredOutput(): boolean {
+    return this.jsonEnabled() || this.outputFormat() === 'sarif'
+  }
+
+  /**
+   * Log a message to stdout, or to stderr when stdout is reserved for structured output.
+   * Unlike `logToStderr`, this still logs with `--json`, for output that was explicitly asked for.
+   */
+  protected logAside(message: string): void {
+    if (this.isStructuredOutput()) {
+      ux.stderr(message)
+    } else {
+      this.log(message)
+    }
+  }
+
+  /**
+   * Deliver the notify targets of fired signals with `--notify`, with a warning that summarizes deliveries that failed.
+   * With `--notify-dry-run`, prints the delivery plan instead, to stderr when stdout is structured output.
+   *
+   * @param github - Contacts `notify.github` targets; without it they are skipped
+   */
+  protected async notify(
+    reports: ReportOutput[],
+    source: NotifySource,
+    notifiers: NotifiersConfig = {},
+    github?: GithubNotifier,
+  ): Promise<void> {
+    const flags = this.flags as {notify?: boolean; 'notify-dry-run'?: boolean}
+    const plan = planNotifications(reports)
+    if (flags['notify-dry-run']) {
+      this.logAside(JSON.stringify(plan, null, 2))
+      return
+    }
+
+    if (!flags.notify) {
+      if (plan.length > 0) {
+        const count = `${plan.length} ${plan.length === 1 ? 'notification' : 'notifications'}`
+        this.logToStderr(`Skipped ${count}; pass --notify to deliver them`)
+      }
+
+      return
+    }
+
+    const results = await sendNotifications(reports, notifiers, source, github)
+    this.debug('notifications', results)
+
+    const failed = results.filter((result) => result.status === 'failed')
+    if (failed.length > 0) {
+      this.warn(
+        [
+          `${failed.length} of ${results.length} notifications could not be delivered:`,
+          ...failed.map((result) => `- ${result.channel} ${result.target}: ${result.error}`),
+        ].join('\n'),
+      )
+    }
+  }
+
   /**
    * Output reports.
    * When JSON is enabled, returns data for oclif to stringify including concerns.
+   * With `--format sarif`, logs a SARIF log to stdout.
    * Otherwise, logs text output to stdout.
+   * With `--fail-on`, sets a failing exit status when any report meets the threshold.
+   * Signals that failed on a file are listed in a warning and also set a failing exit status.
    */
-  protected outputReports(options: {reports: ReportOutput[]}): JsonOutput | void {
-    const {reports} = options
-
-    const jsonReports = reports.map(
-      (report) =>
-        report.metadata || {
-          diffText: '',
-          fileName: '',
-          message: report.content,
-        },
-    )
+  protected outputReports(options: {errors?: SignalError[]; reports: ReportOutput[]}): JsonOutput | void {
+    const {errors = [], reports} = options
+    this.failOnSeverity(reports)
+    this.warnSignalErrors(errors)
+
+    const jsonReports = reports.map((report): ReportMetadata => {
+      const metadata = report.metadata || {diffText: '', fileName: '', message: report.content}
+      return {...metadata, id: getReportFingerprint(metadata)}
+    })
 
     if (this.jsonEnabled()) {
       // Return for oclif to handle JSON output
       return {reports: jsonReports}
     }
 
+    if (this.outputFormat() === 'sarif') {
+      const sarif = createSarifLog(reports, {
+        informationUri: this.config.pjson.homepage,
+        name: this.config.bin,
+        version: this.config.version,
+      })
+      this.log(JSON.stringify(sarif, null, 2))
+      return
+    }
+
     // Normal text output
     for (const report of reports) {
       this.log(report.content)
     }
   }
+
+  /**
+   * Set exit status 1 when reports meet the `--fail-on` threshold.
+   * The status is set rather than exiting, so the reports are still output.
+   */
+  private failOnSeverity(reports: ReportOutput[]): void {
+    const threshold = (this.flags as {'fail-on'?: Severity})['fail-on']
+    if (!threshold) return
+
+    const failing = reports.filter((report) => meetsSeverity(report.metadata?.severity ?? 'info', threshold))
+    if (failing.length > 0) {
+      this.logToStderr(`${failing.length} of ${reports.length} reports have severity ${threshold} or higher`)
+      process.exitCode = 1
+    }
+  }
+
+  private outputFormat(): OutputFormat {
+    return (this.flags as {format?: OutputFormat}).format ?? 'text'
+  }
+
+  /**
+   * Warn about signals that failed on a file and set exit status 1, as their findings are missing.
+   */
+  private warnSignalErrors(errors: SignalError[]): void {
+    if (errors.length === 0) return
+
+    const summary = `${errors.length} ${errors.length === 1 ? 'signal' : 'signals'} failed; their findings are missing:`
+    this.warn([summary, ...errors.map((error) => `- ${error.message}`)].join('\n'))
+    process.exitCode = 1
+  }
 }
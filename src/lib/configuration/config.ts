@@ -4,7 +4,7 @@
 // Core concepts:
 // - Concern: An area of governance interest (security, api-contracts, etc.)
 // - Signal: What to detect and how to respond (watch + report + notify)
-// - Watch: File patterns + extraction type (jq, regex, tsq, ast-grep, xpath)
+// - Watch: File patterns + extraction type (jq, regex, tsq, ast-grep, xpath, yaml, toml)
 // - Report: Output format (type + template)
 // - Notify: Channel → target dictionary
 // =============================================================================
@@ -18,25 +18,49 @@
  * Base properties shared by all watch types.
  */
 export interface WatchBase {
+  /**
+   * Glob pattern(s) for files to skip even when they match `include`.
+   * Uses minimatch syntax for pattern matching.
+   *
+   * @example "**\/*.test.ts"
+   * @example ["**\/*.test.ts", "**\/generated/**"]
+   */
+  exclude?: string | string[]
   /**
    * Glob pattern(s) for files to watch.
    * Can be a single pattern or an array of patterns.
    * Uses minimatch syntax for pattern matching.
+   * Patterns starting with `!` exclude matching files, like entries in `exclude`.
    *
    * @example "package.json"
    * @example ["package.json", "yarn.lock"]
    * @example "src/**\/*.ts"
+   * @example ["src/**\/*.ts", "!**\/*.test.ts"]
    */
   include: string | string[]
+  /**
+   * Which matches count: `file` (default) compares everything the watch extracts from each version,
+   * `changed-lines` only matches overlapping inserted or deleted lines, and `changed-hunks` only matches
+   * overlapping a diff hunk, including its context lines.
+   * Needs located matches: jq, yaml and toml queries must select values by path, and XPath expressions nodes.
+   */
+  scope?: WatchScope
 }
 
+/**
+ * Which part of a changed file a watch considers.
+ */
+export type WatchScope = 'changed-hunks' | 'changed-lines' | 'file'
+
 /**
  * Configuration for the jq watch type.
- * Uses the jq command-line tool to extract/transform JSON content.
+ * Uses an embedded jq to extract/transform JSON content; no jq binary is required.
  */
 export interface JqWatch extends WatchBase {
   /**
    * The jq query expression to apply to JSON content.
+   * Values selected by a path expression like `.dependencies.express` are located in the file;
+   * values the query computes, like `.dependencies | keys`, are not.
    * See https://jqlang.github.io/jq/manual/ for syntax reference.
    */
   query: string
@@ -95,20 +119,192 @@ export interface AstGrepPatternObject {
   selector: string
 }
 
+/**
+ * An ast-grep rule. A node matches when it satisfies every field that is set.
+ * See https://ast-grep.github.io/reference/rule.html for the rule language.
+ */
+export interface AstGrepRule {
+  /**
+   * Matches when the node satisfies all of the rules. Metavariables captured by one rule are shared with the next.
+   */
+  all?: AstGrepRule[]
+  /**
+   * Matches when the node satisfies any of the rules.
+   */
+  any?: AstGrepRule[]
+  /**
+   * Matches when the node comes after a node matching the rule.
+   */
+  follows?: AstGrepRelationalRule
+  /**
+   * Matches when the node has a descendant matching the rule.
+   */
+  has?: AstGrepRelationalRule
+  /**
+   * Matches when the node is inside a node matching the rule.
+   */
+  inside?: AstGrepRelationalRule
+  /**
+   * Matches nodes of a tree-sitter kind.
+   *
+   * @example "call_expression"
+   * @example "try_statement"
+   */
+  kind?: string
+  /**
+   * Matches when the node satisfies the utility rule with this name, defined in the watch's `utils`.
+   */
+  matches?: string
+  /**
+   * Matches when the node does not satisfy the rule.
+   */
+  not?: AstGrepRule
+  /**
+   * Matches nodes by their position among their named siblings, starting at 1.
+   * Accepts a number, an An+B formula like "2n+1", or an object with more options.
+   */
+  nthChild?: AstGrepNthChild | number | string
+  /**
+   * Matches nodes against a code pattern. Can be a string or object with context/selector.
+   */
+  pattern?: AstGrepPatternObject | string
+  /**
+   * Matches when the node comes before a node matching the rule.
+   */
+  precedes?: AstGrepRelationalRule
+  /**
+   * Matches nodes whose text matches a Rust regular expression.
+   */
+  regex?: string
+}
+
+/**
+ * A rule used by `inside`, `has`, `follows` and `precedes`.
+ */
+export interface AstGrepRelationalRule extends AstGrepRule {
+  /**
+   * Only match the related node in this field of its parent.
+   *
+   * @example "body"
+   */
+  field?: string
+  /**
+   * How far to search: `neighbor` (default) only checks the direct parent, child or sibling,
+   * `end` searches all the way up, down or across, and a rule stops at the first node matching it.
+   */
+  stopBy?: 'end' | 'neighbor' | AstGrepRule
+}
+
+/**
+ * Position options for `nthChild`.
+ */
+export interface AstGrepNthChild {
+  /**
+   * Only count siblings matching this rule.
+   */
+  ofRule?: AstGrepRule
+  /**
+   * A number or An+B formula.
+   */
+  position: number | string
+  /**
+   * Count from the last sibling instead of the first.
+   */
+  reverse?: boolean
+}
+
+/**
+ * Derive a new metavariable from a captured one.
+ * See https://ast-grep.github.io/reference/yaml/transformation.html for details.
+ */
+export interface AstGrepTransformation {
+  /**
+   * Change the case of the captured text.
+   */
+  convert?: {
+    separatedBy?: Array<'caseChange' | 'dash' | 'dot' | 'slash' | 'space' | 'underscore'>
+    source: string
+    toCase: 'camelCase' | 'capitalize' | 'kebabCase' | 'lowerCase' | 'pascalCase' | 'snakeCase' | 'upperCase'
+  }
+  /**
+   * Replace regex matches in the captured text.
+   */
+  replace?: {
+    by: string
+    replace: string
+    source: string
+  }
+  /**
+   * Take part of the captured text, by character index. Negative indexes count from the end.
+   */
+  substring?: {
+    endChar?: number
+    source: string
+    startChar?: number
+  }
+}
+
+/**
+ * Languages ast-grep watches can parse, with the aliases the ast-grep CLI accepts for them.
+ * JavaScript, TypeScript, TSX, HTML and CSS are built into ast-grep; C, C++, Go, Java, JSON, Python and Rust
+ * are loaded from their `@ast-grep/lang-*` grammar packages. Other languages are not supported yet.
+ */
+export type AstGrepLanguage =
+  | 'c'
+  | 'c++'
+  | 'cc'
+  | 'cpp'
+  | 'css'
+  | 'cxx'
+  | 'go'
+  | 'golang'
+  | 'html'
+  | 'java'
+  | 'javascript'
+  | 'js'
+  | 'json'
+  | 'jsx'
+  | 'py'
+  | 'python'
+  | 'rs'
+  | 'rust'
+  | 'ts'
+  | 'tsx'
+  | 'typescript'
+
 /**
  * Configuration for the ast-grep watch type.
- * Extracts AST nodes using ast-grep's pattern syntax.
+ * Extracts AST nodes using ast-grep's pattern syntax or a full ast-grep rule.
+ * Either `pattern` or `rule` is required.
  */
 export interface AstGrepWatch extends WatchBase {
+  /**
+   * Extra rules that captured metavariables must satisfy, keyed by metavariable name without `$`.
+   */
+  constraints?: Record<string, AstGrepRule>
   /**
    * The language to parse the code as.
    */
-  language: string
+  language: AstGrepLanguage
   /**
    * The pattern to match against. Can be a string or object with context/selector.
+   * Shorthand for a `rule` with only a `pattern`; with a `rule` as well, matches must satisfy both.
    */
-  pattern: AstGrepPatternObject | string
+  pattern?: AstGrepPatternObject | string
+  /**
+   * An ast-grep rule combining patterns, node kinds, relational rules (`inside`, `has`, `follows`, `precedes`)
+   * and composite rules (`all`, `any`, `not`, `matches`).
+   */
+  rule?: AstGrepRule
+  /**
+   * New metavariables derived from captured ones, available to reports like any other metavariable.
+   */
+  transform?: Record<string, AstGrepTransformation | string>
   type: 'ast-grep'
+  /**
+   * Named rules that `matches` can refer to.
+   */
+  utils?: Record<string, AstGrepRule>
 }
 
 /**
@@ -127,10 +323,40 @@ export interface XPathWatch extends WatchBase {
   type: 'xpath'
 }
 
+/**
+ * Configuration for the yaml watch type.
+ * Parses YAML (including multi-document files) and applies a jq query to the result.
+ */
+export interface YamlWatch extends WatchBase {
+  /**
+   * The jq query expression to apply to the parsed YAML.
+   * Multi-document files are queried one document at a time.
+   * Selected values are located in the file as for a jq watch.
+   * See https://jqlang.github.io/jq/manual/ for syntax reference.
+   */
+  query: string
+  type: 'yaml'
+}
+
+/**
+ * Configuration for the toml watch type.
+ * Parses TOML (e.g. Cargo.toml, pyproject.toml) and applies a jq query to the result.
+ */
+export interface TomlWatch extends WatchBase {
+  /**
+   * The jq query expression to apply to the parsed TOML.
+   * Dates and times are represented as strings.
+   * Selected values are located at the key or table header that defines them.
+   * See https://jqlang.github.io/jq/manual/ for syntax reference.
+   */
+  query: string
+  type: 'toml'
+}
+
 /**
  * Union type of all supported watch configurations.
  */
-export type WatchConfig = AstGrepWatch | JqWatch | RegexWatch | TsqWatch | XPathWatch
+export type WatchConfig = AstGrepWatch | JqWatch | RegexWatch | TomlWatch | TsqWatch | XPathWatch | YamlWatch
 
 /**
  * All supported watch type names.
@@ -157,15 +383,72 @@ export interface HandlebarsReport {
   type: 'handlebars'
 }
 
-// Future report types can be added here:
-// export interface JsonReport { type: 'json'; schema?: string }
-// export interface SarifReport { type: 'sarif' }
+/**
+ * A sarif report renders the message of a SARIF result.
+ * Signals become results, concerns become rules, and changed matches become locations
+ * when the output is written with `--format sarif`.
+ */
+export interface SarifReport {
+  /**
+   * Optional handlebars template for the Markdown variant of the message.
+   * Receives the same context as `template`.
+   */
+  markdown?: string
+  /**
+   * Handlebars template for the plain text message of the result.
+   * Receives watch result context including diffText, filePath, etc.
+   */
+  template: string
+  /**
+   * Report type discriminant.
+   */
+  type: 'sarif'
+}
+
+/**
+ * A json report produces a structured object per finding instead of rendered text.
+ * Each field is either a handlebars template, rendered to a string, or a selector
+ * that picks a value out of the watch result as is.
+ */
+export interface JsonReport {
+  /**
+   * Output field names mapped to a handlebars template or a selector.
+   * Templates and selectors see the same context as handlebars report templates.
+   */
+  fields: Record<string, JsonReportField>
+  /**
+   * Optional JSON Schema that every produced object must satisfy.
+   */
+  schema?: Record<string, unknown>
+  /**
+   * Report type discriminant.
+   */
+  type: 'json'
+}
+
+/**
+ * A json report field: a handlebars template rendered to a string, or a selector.
+ */
+export type JsonReportField = JsonReportSelector | string
+
+/**
+ * Selects a value from the watch result without converting it to a string.
+ */
+export interface JsonReportSelector {
+  /**
+   * JSONPath-like path into the report context, with `.key`, `[index]`, `['key']` and `[*]` segments.
+   * A leading `$` is optional. Selectors with `[*]` produce an array.
+   * @example "filePath"
+   * @example "right.captures[*].version"
+   * @example "$.locations.new[0].startLine"
+   */
+  select: string
+}
 
 /**
  * Union type of all supported report configurations.
- * Currently only handlebars is supported.
  */
-export type ReportConfig = HandlebarsReport
+export type ReportConfig = HandlebarsReport | JsonReport | SarifReport
 
 /**
  * All supported report type names.
@@ -179,6 +462,8 @@ export type ReportType = ReportConfig['type']
 
 /**
  * Notification channel types and their target formats.
+ * Targets can be Handlebars templates, rendered with the same data as report templates,
+ * e.g. `#team-{{pathSegments.[1]}}`. Targets that render empty are skipped.
  */
 export interface NotifyConfig {
   /**
@@ -187,13 +472,16 @@ export interface NotifyConfig {
    */
   email?: string
   /**
-   * GitHub username or team to mention/request review.
-   * @example "@security-team"
+   * GitHub username or `org/team` slug to request a review from when running `distill pr --notify`.
+   * Targets listed as a stakeholder of the concern are contacted per their `contactMethod` instead.
+   * @example "@octocat"
+   * @example "my-org/security-team"
    */
   github?: string
   /**
-   * Jira issue key to comment on.
+   * Jira issue key to comment on, or project key to create an issue in.
    * @example "SEC-123"
+   * @example "SEC"
    */
   jira?: string
   /**
@@ -213,11 +501,26 @@ export interface NotifyConfig {
 // A signal combines watch + report + notify into a complete detection unit.
 // =============================================================================
 
+/**
+ * How serious a finding is, from least to most.
+ * - info: worth knowing about
+ * - warning: should be looked at before merging
+ * - error: must be addressed; fails runs with `--fail-on error`
+ */
+export type Severity = 'error' | 'info' | 'warning'
+
 /**
  * A signal defines what to detect (watch), how to format output (report),
  * and who to notify when triggered.
  */
 export interface Signal {
+  /**
+   * Stable identifier for the signal, unique within its concern.
+   * Reports carry it as `signalId` so downstream tooling can group and dedupe findings.
+   * Defaults to the `#defined/signals/<name>` reference or the signal's index in the concern.
+   * @example "runtime-dependencies"
+   */
+  id?: string
   /**
    * Notification channels and targets.
    * Each key is a channel type, value is the target.
@@ -227,6 +530,11 @@ export interface Signal {
    * How to format the output when the signal triggers.
    */
   report: ReportConfig | ReportRef
+  /**
+   * How serious the signal's findings are.
+   * Defaults to the concern's `severity`, or `info`.
+   */
+  severity?: Severity
   /**
    * What to watch for - file patterns and extraction configuration.
    */
@@ -266,16 +574,70 @@ export type SignalRef = Signal | UseReference
 // A concern represents an area of governance interest.
 // =============================================================================
 
+/**
+ * How a stakeholder wants to be contacted when a concern's signals fire.
+ * - github-reviewer-request: request a review on the pull request
+ * - github-comment-mention: mention them in a pull request comment
+ */
+export type ContactMethod = 'github-comment-mention' | 'github-reviewer-request'
+
+/**
+ * A team or individual with an interest in a concern.
+ */
+export interface Stakeholder {
+  /**
+   * How the stakeholder wants to be contacted.
+   */
+  contactMethod: ContactMethod
+  /**
+   * What the stakeholder is responsible for.
+   */
+  description?: string
+  /**
+   * GitHub username or `org/team` slug used by the github-* contact methods.
+   * @example "octocat"
+   * @example "my-org/security-team"
+   */
+  github?: string
+  /**
+   * Display name of the team or individual.
+   * @example "Security Team"
+   */
+  name: string
+}
+
 /**
  * A concern is an area of governance interest (e.g., security, api-contracts).
  * It contains signals that define what to watch and how to respond.
  */
 export interface Concern {
+  /**
+   * Glob pattern(s) for files none of this concern's signals should process.
+   *
+   * @example "**\/generated/**"
+   */
+  exclude?: string | string[]
+  /**
+   * Glob pattern(s) limiting which files this concern's signals process.
+   * Applies in addition to each watch's own `include`.
+   * Patterns starting with `!` exclude matching files.
+   *
+   * @example "services/payments/**"
+   */
+  include?: string | string[]
+  /**
+   * Default severity of this concern's signals, which is otherwise `info`.
+   */
+  severity?: Severity
   /**
    * Signals attached to this concern.
    * Each signal defines what to watch and how to respond.
    */
   signals: SignalRef[]
+  /**
+   * Teams or individuals to contact when any of this concern's signals fire.
+   */
+  stakeholders?: Stakeholder[]
 }
 
 // =============================================================================
@@ -322,12 +684,163 @@ export interface DistillConfig {
    * Reusable definitions that can be referenced throughout the configuration.
    */
   defined?: DefinedBlock
+  /**
+   * Glob patterns for files that no concern should process, such as vendored or generated code.
+   *
+   * @example ["vendor/**", "**\/*.min.js"]
+   */
+  ignore?: string[]
+  /**
+   * Settings for delivering the `notify` targets of fired signals.
+   */
+  notifiers?: NotifiersConfig
+}
+
+// =============================================================================
+// NOTIFIER SETTINGS
+// How notify targets are delivered. Credentials come from the environment.
+// =============================================================================
+
+/**
+ * Delivery settings per notify channel.
+ */
+export interface NotifiersConfig {
+  /**
+   * Email delivery settings.
+   */
+  email?: EmailNotifierConfig
+  /**
+   * Jira delivery settings.
+   */
+  jira?: JiraNotifierConfig
+  /**
+   * Slack delivery settings.
+   */
+  slack?: SlackNotifierConfig
+  /**
+   * Webhook delivery settings.
+   */
+  webhook?: WebhookNotifierConfig
+}
+
+/**
+ * Email delivery settings. Each setting falls back to an environment variable, and the SMTP login
+ * comes from `SMTP_USER` and `SMTP_PASSWORD`. Email is skipped when no host is set.
+ */
+export interface EmailNotifierConfig {
+  /**
+   * Sender address. Falls back to `SMTP_FROM`, then `SMTP_USER`.
+   * @example "distill <distill@example.com>"
+   */
+  from?: string
+  /**
+   * SMTP server host. Falls back to `SMTP_HOST`.
+   * @example "smtp.example.com"
+   */
+  host?: string
+  /**
+   * SMTP server port. Falls back to `SMTP_PORT`, then 465 with `secure` or 587 without.
+   */
+  port?: number
+  /**
+   * Connect with TLS from the start, usually on port 465. Otherwise the connection is upgraded
+   * with STARTTLS when the server offers it. Falls back to `SMTP_SECURE=true`.
+   */
+  secure?: boolean
+}
+
+/**
+ * Jira delivery settings.
+ * Requests are authenticated with `JIRA_EMAIL` and `JIRA_API_TOKEN`, or with `JIRA_API_TOKEN` alone as a
+ * personal access token. Jira is skipped when no site URL or token is set.
+ */
+export interface JiraNotifierConfig {
+  /**
+   * Handlebars template for the description of created issues, in Jira wiki markup.
+   * Has `title`, `origin`, `pullRequest`, `repository`, `refs` and `concerns`,
+   * each with a `concernId` and its `reports`.
+   */
+  description?: string
+  /**
+   * Issue type of created issues. Defaults to `Task`.
+   */
+  issueType?: string
+  /**
+   * Handlebars template for the summary of created issues, with the same data as `description`.
+   * @example "{{title}} in {{origin}}"
+   */
+  summary?: string
+  /**
+   * Base URL of the Jira site. Falls back to `JIRA_URL`.
+   * @example "https://example.atlassian.net"
+   */
+  url?: string
+}
+
+/**
+ * Slack delivery settings.
+ * Messages are posted with the bot token in `SLACK_BOT_TOKEN`, or to the incoming webhook in `SLACK_WEBHOOK_URL`.
+ */
+export interface SlackNotifierConfig {
+  /**
+   * Base URL of the Slack Web API, used with a bot token.
+   * @example "https://slack.com/api"
+   */
+  apiUrl?: string
+  /**
+   * Minimum milliseconds between two messages, to stay within Slack's rate limits. Defaults to 1000.
+   */
+  interval?: number
+  /**
+   * How many times to retry a message that failed or was rate limited. Defaults to 3.
+   */
+  retries?: number
+}
+
+/**
+ * Webhook delivery settings.
+ * Payloads are signed with the secret in `DISTILL_WEBHOOK_SECRET`.
+ */
+export interface WebhookNotifierConfig {
+  /**
+   * Milliseconds to wait before the first retry, doubled for each retry after it. Defaults to 1000.
+   */
+  backoff?: number
+  /**
+   * How many times to retry a delivery that failed. Defaults to 3.
+   */
+  retries?: number
+  /**
+   * Milliseconds to wait for the receiver to respond. Defaults to 10000.
+   */
+  timeout?: number
 }
 
 // =============================================================================
 // FILTER RESULT (kept for processing pipeline)
 // =============================================================================
 
+/**
+ * A range in a source file.
+ * Lines and columns are 1-based; `endColumn` is the column just past the last character.
+ */
+export interface SourceRange {
+  endColumn: number
+  endLine: number
+  startColumn: number
+  startLine: number
+}
+
+/**
+ * A node or match extracted by a watch, with its position in the file it came from.
+ */
+export interface SourceMatch {
+  /** Named captures of the match, also listed in the side's `captures` */
+  captures?: Record<string, string>
+  range: SourceRange
+  text: string
+}
+
 /**
  * Result of applying a watch to file versions.
  */
@@ -341,15 +854,16 @@ export interface FilterResult {
    */
   diffText: string
   /**
-   * The artifact extracted from the old version.
+   * The artifact extracted from the old version, with the named captures of each match
+   * (regex groups, tree-sitter captures, ast-grep metavariables) and where each match is in the file.
    */
-  left: {artifact: string}
+  left: {artifact: string; captures?: Record<string, string>[]; matches?: SourceMatch[]}
   /**
    * Line range within the filtered artifact.
    */
   lineRange?: {end: number; start: number}
   /**
-   * The artifact extracted from the new version.
+   * The artifact extracted from the new version, with the named captures and position of each match.
    */
-  right: {artifact: string}
+  right: {artifact: string; captures?: Record<string, string>[]; matches?: SourceMatch[]}
 }
@@ -1,9 +1,29 @@
 import {readFile} from 'node:fs/promises'
-import {parse as parseYaml} from 'yaml'
 
 import type {DistillConfig} from './config.js'
 
+import {ConfigValidationError, validateConfigSource} from './validator.js'
+
+/**
+ * Load and validate a distill configuration file.
+ *
+ * @throws ConfigValidationError listing every problem found, with YAML line/column
+ */
 export async function loadConfig(path: string): Promise<DistillConfig> {
   const content = await readFile(path, 'utf8')
-  return parseYaml(content) as DistillConfig
+  return parseConfig(content, path)
+}
+
+/**
+ * Parse and validate distill configuration from YAML source.
+ *
+ * @throws ConfigValidationError listing every problem found, with YAML line/column
+ */
+export function parseConfig(content: string, source?: string): DistillConfig {
+  const {config, issues} = validateConfigSource(content)
+  if (issues.length > 0) {
+    throw new ConfigValidationError(issues, source)
+  }
+
+  return config
 }
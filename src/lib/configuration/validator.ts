@@ -0,0 +1,492 @@
+import {Ajv, type ErrorObject, type ValidateFunction} from 'ajv'
+import {createRequire} from 'node:module'
+import {type Document, LineCounter, parseDocument} from 'yaml'
+
+import type {DefinedBlock, DistillConfig} from './config.js'
+
+import {isUseReference, resolveReport, resolveSignal, resolveWatch} from './resolver.js'
+
+// The schema is generated from config.ts by `npm run generate-schema` and lives at the package root.
+const require = createRequire(import.meta.url)
+const distillSchema = require('../../../distill-schema.json') as JsonSchema
+
+/**
+ * The parts of the JSON Schema emitted by ts-json-schema-generator that error reporting looks at.
+ * Validation itself is left to ajv.
+ */
+interface JsonSchema {
+  $ref?: string
+  additionalProperties?: boolean | JsonSchema
+  anyOf?: JsonSchema[]
+  const?: unknown
+  definitions?: Record<string, JsonSchema>
+  enum?: unknown[]
+  items?: JsonSchema
+  properties?: Record<string, JsonSchema>
+  required?: string[]
+  type?: string | string[]
+}
+
+/**
+ * Path to a value in the configuration document, e.g. `['concerns', 'security', 'signals', 0]`.
+ */
+export type ConfigPath = Array<number | string>
+
+/**
+ * A single problem found in a configuration document.
+ */
+export interface ConfigIssue {
+  /** 1-based column in the YAML source, when known */
+  column?: number
+  /** Concern the problem belongs to, when it is inside `concerns.<id>` */
+  concernId?: string
+  /** 1-based line in the YAML source, when known */
+  line?: number
+  /** Human-readable description of the problem */
+  message: string
+  /** Dotted path to the offending value, e.g. `concerns.security.signals[0].watch.type` */
+  path: string
+  /** Index of the signal the problem belongs to, when it is inside `concerns.<id>.signals` */
+  signalIndex?: number
+}
+
+/**
+ * Thrown by the loader when a configuration document is invalid.
+ * Carries every issue found, not just the first.
+ */
+export class ConfigValidationError extends Error {
+  constructor(
+    public readonly issues: ConfigIssue[],
+    public readonly source?: string,
+  ) {
+    super(formatIssues(issues, source))
+    this.name = 'ConfigValidationError'
+  }
+}
+
+/**
+ * Result of validating a configuration document.
+ */
+export interface ValidationResult {
+  /** The parsed configuration; only safe to use when `issues` is empty */
+  config: DistillConfig
+  /** Create an issue located at the given path in the source document */
+  issueAt: (path: ConfigPath, message: string) => ConfigIssue
+  issues: ConfigIssue[]
+}
+
+/**
+ * Parse and validate YAML configuration source against the DistillConfig schema,
+ * then resolve every `#defined/...` reference.
+ */
+export function validateConfigSource(content: string): ValidationResult {
+  const lineCounter = new LineCounter()
+  const doc = parseDocument(content, {lineCounter})
+
+  const issueAt = (path: ConfigPath, message: string) => createIssue(doc, lineCounter, path, message)
+
+  if (doc.errors.length > 0) {
+    return {
+      config: {} as DistillConfig,
+      issueAt,
+      issues: doc.errors.map((error) => ({
+        column: error.linePos?.[0].col,
+        line: error.linePos?.[0].line,
+        message: error.message.split('\n')[0],
+        path: '',
+      })),
+    }
+  }
+
+  const config = doc.toJS() as DistillConfig
+  const issues: ConfigIssue[] = []
+  const report = (path: ConfigPath, message: string) => issues.push(issueAt(path, message))
+
+  validateValue(config, distillSchema, [], report)
+  validateReferences(config, report)
+
+  return {config, issueAt, issues}
+}
+
+/**
+ * Format a list of issues as a multi-line message.
+ */
+export function formatIssues(issues: ConfigIssue[], source?: string): string {
+  const count = `${issues.length} ${issues.length === 1 ? 'problem' : 'problems'}`
+  const header = `Invalid configuration${source ? ` in ${source}` : ''} (${count}):`
+  const lines = issues.map((issue) => {
+    const location = issue.line === undefined ? '' : `${issue.line}:${issue.column ?? 1} `
+    const subject = issue.path ? `${issue.path}: ` : ''
+    return `  ${location}${subject}${issue.message}`
+  })
+  return [header, ...lines].join('\n')
+}
+
+// =============================================================================
+// SCHEMA VALIDATION
+// =============================================================================
+
+type Reporter = (path: ConfigPath, message: string) => void
+
+// Union types like `number | string` are how the schema generator writes optional forms, not mistakes
+const ajv = new Ajv({allErrors: true, strictTypes: false, verbose: true})
+const validators = new WeakMap<JsonSchema, ValidateFunction>()
+
+function validateValue(value: unknown, schema: JsonSchema, path: ConfigPath, report: Reporter): void {
+  const validate = compile(schema)
+  if (validate(value)) return
+
+  const errors = validate.errors ?? []
+  // A failed union carries the errors of every branch, so report it once, for the branch the value was meant for
+  const unions = errors.filter((error) => error.keyword === 'anyOf')
+  const reported = new Set<ErrorObject>()
+
+  for (const error of errors) {
+    const union = outermostUnion(unions, error)
+    if (union) {
+      if (!reported.has(union)) {
+        reported.add(union)
+        const branches = union.schema as JsonSchema[]
+        validateUnion(union.data, {anyOf: branches}, toPath(value, union.instancePath, path), report)
+      }
+
+      continue
+    }
+
+    const errorPath = toPath(value, error.instancePath, path)
+    if (error.keyword === 'additionalProperties') {
+      const key = error.params.additionalProperty as string
+      const known = Object.keys((error.parentSchema as JsonSchema | undefined)?.properties ?? {})
+      const hint = known.length > 0 ? ` (expected one of: ${known.join(', ')})` : ''
+      report([...errorPath, key], `Unknown property "${key}"${hint}`)
+    } else {
+      report(errorPath, describeError(error))
+    }
+  }
+}
+
+/**
+ * Validate against an `anyOf` union. Unions in DistillConfig are discriminated either by
+ * a `use` key (references) or by a `type` constant, so pick the matching branch and report
+ * its errors rather than every branch's.
+ */
+function validateUnion(value: unknown, schema: JsonSchema, path: ConfigPath, report: Reporter): void {
+  const branches = flattenUnion(schema)
+  const fitting = branches.filter((branch) => !branch.type || matchesType(value, branch.type))
+
+  // Values that fit no branch, or only keywords like `stopBy: end`, are best told what the union accepts
+  if (fitting.length === 0 || (!isPlainObject(value) && fitting.every((b) => b.const !== undefined || b.enum))) {
+    const expected = [...new Set(branches.flatMap((branch) => describeSchema(branch)))]
+    report(path, `Expected ${expected.join(' or ')} but got ${describe(value)}`)
+    return
+  }
+
+  let candidates = fitting
+  if (isPlainObject(value) && 'use' in value) {
+    candidates = fitting.filter((branch) => branch.properties?.use)
+  } else if (isPlainObject(value)) {
+    const discriminated = fitting.filter((branch) => branch.properties?.type?.const !== undefined)
+    if (discriminated.length > 0) {
+      const allowed = discriminated.map((branch) => JSON.stringify(branch.properties!.type.const))
+      if (!('type' in value)) {
+        report(path, `Missing required property "type" (expected one of: ${allowed.join(', ')})`)
+        // Surface likely typos such as `tpye:` alongside the missing discriminant
+        const known = new Set(discriminated.flatMap((branch) => Object.keys(branch.properties ?? {})))
+        for (const key of Object.keys(value).filter((k) => !known.has(k))) {
+          report([...path, key], `Unknown property "${key}"`)
+        }
+
+        return
+      }
+
+      candidates = discriminated.filter((branch) => branch.properties!.type.const === value.type)
+      if (candidates.length === 0) {
+        report([...path, 'type'], `Unknown type ${describe(value.type)} (expected one of: ${allowed.join(', ')})`)
+        return
+      }
+    }
+  }
+
+  if (candidates.length === 0) {
+    candidates = fitting
+  }
+
+  const attempts = candidates.map((branch) => {
+    const errors: Array<{message: string; path: ConfigPath}> = []
+    validateValue(value, branch, path, (p, message) => errors.push({message, path: p}))
+    return errors
+  })
+  const best = attempts.reduce((a, b) => (b.length < a.length ? b : a))
+  for (const error of best) {
+    report(error.path, error.message)
+  }
+}
+
+function compile(schema: JsonSchema): ValidateFunction {
+  let validate = validators.get(schema)
+  if (!validate) {
+    // Branches are compiled on their own to pick out their errors, so each carries the definitions it refers to
+    validate = ajv.compile({...schema, definitions: distillSchema.definitions})
+    validators.set(schema, validate)
+  }
+
+  return validate
+}
+
+/**
+ * Find the outermost union that failed around an error, if any.
+ * Nested unions, like a watch that is either a reference or one of the watch types, fail at the same path.
+ */
+function outermostUnion(unions: ErrorObject[], error: ErrorObject): ErrorObject | undefined {
+  let outermost: ErrorObject | undefined
+  for (const union of unions) {
+    const within = error.instancePath === union.instancePath || error.instancePath.startsWith(`${union.instancePath}/`)
+    if (within && (!outermost || union.instancePath.length <= outermost.instancePath.length)) {
+      outermost = union
+    }
+  }
+
+  return outermost
+}
+
+/**
+ * Turn a JSON pointer into the validated value into a config path, with array indices as numbers.
+ */
+function toPath(value: unknown, pointer: string, base: ConfigPath): ConfigPath {
+  const path = [...base]
+  let current = value
+  for (const segment of pointer.split('/').slice(1)) {
+    const key = segment.replaceAll('~1', '/').replaceAll('~0', '~')
+    if (Array.isArray(current)) {
+      path.push(Number(key))
+      current = current[Number(key)]
+    } else {
+      path.push(key)
+      current = isPlainObject(current) ? current[key] : undefined
+    }
+  }
+
+  return path
+}
+
+function describeError(error: ErrorObject): string {
+  switch (error.keyword) {
+    case 'const': {
+      return `Expected ${JSON.stringify(error.params.allowedValue)} but got ${describe(error.data)}`
+    }
+
+    case 'enum': {
+      const allowed = (error.params.allowedValues as unknown[]).map((v) => JSON.stringify(v)).join(', ')
+      return `Expected one of ${allowed} but got ${describe(error.data)}`
+    }
+
+    case 'required': {
+      return `Missing required property "${error.params.missingProperty}"`
+    }
+
+    case 'type': {
+      return `Expected ${[error.schema].flat().join(' or ')} but got ${describe(error.data)}`
+    }
+
+    default: {
+      return `Value ${error.message ?? 'is invalid'}`
+    }
+  }
+}
+
+function describeSchema(schema: JsonSchema): string[] {
+  if (schema.const !== undefined) return [JSON.stringify(schema.const)]
+  if (schema.enum) return schema.enum.map((value) => JSON.stringify(value))
+  return schema.type ? [schema.type].flat() : ['object']
+}
+
+function flattenUnion(schema: JsonSchema, seen = new Set<JsonSchema>()): JsonSchema[] {
+  const resolved = deref(schema)
+  if (!resolved.anyOf) {
+    if (seen.has(resolved)) return []
+    seen.add(resolved)
+    return [resolved]
+  }
+
+  return resolved.anyOf.flatMap((branch) => flattenUnion(branch, seen))
+}
+
+function deref(schema: JsonSchema): JsonSchema {
+  let current = schema
+  while (current.$ref) {
+    const name = current.$ref.replace(/^#\/definitions\//, '')
+    const target = distillSchema.definitions?.[name]
+    if (!target) {
+      throw new Error(`Unresolvable schema reference: ${current.$ref}`)
+    }
+
+    current = target
+  }
+
+  return current
+}
+
+function matchesType(value: unknown, type: string | string[]): boolean {
+  const types = Array.isArray(type) ? type : [type]
+  return types.some((t) => {
+    switch (t) {
+      case 'array': {
+        return Array.isArray(value)
+      }
+
+      case 'integer': {
+        return Number.isInteger(value)
+      }
+
+      case 'null': {
+        return value === null
+      }
+
+      case 'object': {
+        return isPlainObject(value)
+      }
+
+      default: {
+        return typeof value === t
+      }
+    }
+  })
+}
+
+function isPlainObject(value: unknown): value is Record<string, unknown> {
+  return typeof value === 'object' && value !== null && !Array.isArray(value)
+}
+
+function describe(value: unknown): string {
+  if (value === null || value === undefined) return 'nothing'
+  if (Array.isArray(value)) return 'array'
+  if (typeof value === 'object') return 'object'
+  return `${typeof value} ${JSON.stringify(value)}`
+}
+
+// =============================================================================
+// REFERENCE RESOLUTION
+// =============================================================================
+
+/**
+ * Resolve every `use` reference in concerns and in defined signals so that broken
+ * references are reported at load time instead of while processing a diff.
+ */
+function validateReferences(config: DistillConfig, report: Reporter): void {
+  if (!isPlainObject(config)) return
+
+  const defined = isPlainObject(config.defined) ? config.defined : undefined
+
+  if (isPlainObject(config.concerns)) {
+    for (const [concernId, concern] of Object.entries(config.concerns)) {
+      if (!isPlainObject(concern) || !Array.isArray(concern.signals)) continue
+      const signalIds = new Map<string, number>()
+      for (const [index, signalRef] of concern.signals.entries()) {
+        const path: ConfigPath = ['concerns', concernId, 'signals', index]
+        validateSignalReferences(signalRef, path, defined, report)
+
+        // Explicit ids identify findings downstream, so they must not collide within a concern
+        const id = explicitSignalId(signalRef, defined)
+        if (id === undefined) continue
+        if (signalIds.has(id)) {
+          const key = isUseReference(signalRef) ? 'use' : 'id'
+          report([...path, key], `Duplicate signal id "${id}" (also used by signal ${signalIds.get(id)})`)
+        } else {
+          signalIds.set(id, index)
+        }
+      }
+    }
+  }
+
+  if (defined && isPlainObject(defined.signals)) {
+    for (const [name, signal] of Object.entries(defined.signals)) {
+      validateSignalReferences(signal, ['defined', 'signals', name], defined, report)
+    }
+  }
+}
+
+function explicitSignalId(signalRef: unknown, defined: DefinedBlock | undefined): string | undefined {
+  let signal = signalRef
+  if (isUseReference(signalRef)) {
+    try {
+      signal = resolveSignal(signalRef, defined)
+    } catch {
+      return undefined
+    }
+  }
+
+  return isPlainObject(signal) && typeof signal.id === 'string' ? signal.id : undefined
+}
+
+function validateSignalReferences(
+  signal: unknown,
+  path: ConfigPath,
+  defined: DefinedBlock | undefined,
+  report: Reporter,
+): void {
+  if (!isPlainObject(signal)) return
+
+  if (isUseReference(signal)) {
+    // The referenced signal's own watch and report are checked with the rest of defined.signals
+    try {
+      resolveSignal(signal, defined)
+    } catch (error) {
+      report([...path, 'use'], (error as Error).message)
+    }
+
+    return
+  }
+
+  if (isUseReference(signal.watch)) {
+    try {
+      resolveWatch(signal.watch, defined)
+    } catch (error) {
+      report([...path, 'watch', 'use'], (error as Error).message)
+    }
+  }
+
+  if (isUseReference(signal.report)) {
+    try {
+      resolveReport(signal.report, defined)
+    } catch (error) {
+      report([...path, 'report', 'use'], (error as Error).message)
+    }
+  }
+}
+
+// =============================================================================
+// ISSUE LOCATION
+// =============================================================================
+
+function createIssue(doc: Document, lineCounter: LineCounter, path: ConfigPath, message: string): ConfigIssue {
+  const issue: ConfigIssue = {message, path: formatPath(path)}
+
+  // Walk up the path until we find a node that exists (missing properties point at their parent)
+  for (let depth = path.length; depth >= 0; depth--) {
+    const node = doc.getIn(path.slice(0, depth), true) as undefined | {range?: [number, number, number]}
+    if (node?.range) {
+      const {col, line} = lineCounter.linePos(node.range[0])
+      issue.line = line
+      issue.column = col
+      break
+    }
+  }
+
+  if (path[0] === 'concerns' && typeof path[1] === 'string') {
+    issue.concernId = path[1]
+    if (path[2] === 'signals' && typeof path[3] === 'number') {
+      issue.signalIndex = path[3]
+    }
+  }
+
+  return issue
+}
+
+function formatPath(path: ConfigPath): string {
+  let result = ''
+  for (const segment of path) {
+    result += typeof segment === 'number' ? `[${segment}]` : `${result ? '.' : ''}${segment}`
+  }
+
+  return result
+}
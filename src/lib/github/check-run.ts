@@ -0,0 +1,167 @@
+/* eslint-disable camelcase -- annotations use the snake_case fields of the GitHub API */
+import type {Octokit} from 'octokit'
+
+import type {Severity} from '../configuration/config.js'
+import type {ReportOutput} from '../reports/index.js'
+
+import {countOf, groupByConcern} from '../notify/index.js'
+import {meetsSeverity} from '../reports/severity.js'
+
+/** Name of the check run distill creates */
+export const CHECK_RUN_NAME = 'distill'
+
+/** GitHub accepts at most this many annotations per create or update request */
+export const MAX_ANNOTATIONS_PER_REQUEST = 50
+
+/** GitHub rejects check run summaries longer than this */
+const MAX_SUMMARY_LENGTH = 65_535
+
+/** Annotation level for each severity */
+const ANNOTATION_LEVELS: Record<Severity, CheckAnnotation['annotation_level']> = {
+  error: 'failure',
+  info: 'notice',
+  warning: 'warning',
+}
+
+/**
+ * Conclusion of the check run when a `warning` or `error` signal fires.
+ * Runs where only `info` signals fire are neutral, and runs where nothing fires succeed.
+ * - `neutral`: report findings without blocking the merge
+ * - `failure`: fail the check, so branch protection can require a review of the findings
+ */
+export type CheckConclusion = 'failure' | 'neutral'
+
+/**
+ * A check run annotation, in the shape the GitHub API expects.
+ */
+export interface CheckAnnotation {
+  annotation_level: 'failure' | 'notice' | 'warning'
+  end_column?: number
+  end_line: number
+  message: string
+  path: string
+  start_column?: number
+  start_line: number
+  title?: string
+}
+
+/**
+ * Where a check run was published and what it reported.
+ */
+export interface CheckRunOutcome {
+  annotations: number
+  conclusion: 'success' | CheckConclusion
+  id: number
+}
+
+/**
+ * Annotate the changed lines of each finding.
+ * Findings without locations in the new file, like those of `jq` watches, annotate the first line of the file.
+ */
+export function getCheckAnnotations(reports: ReportOutput[]): CheckAnnotation[] {
+  return reports.flatMap((report): CheckAnnotation[] => {
+    if (!report.metadata) return []
+    const {concernId, fileName, locations, message, severity, signalId} = report.metadata
+    const base = {
+      annotation_level: ANNOTATION_LEVELS[severity ?? 'info'],
+      message: message || report.content,
+      ...(concernId ? {title: signalId ? `${concernId}: ${signalId}` : concernId} : {}),
+    }
+
+    if (!locations?.new.length) {
+      return [{...base, end_line: 1, path: fileName, start_line: 1}]
+    }
+
+    return locations.new.map((location) => ({
+      ...base,
+      // Columns are only allowed on single-line annotations, and GitHub's end column is inclusive
+      ...(location.startLine === location.endLine
+        ? {end_column: Math.max(location.startColumn, location.endColumn - 1), start_column: location.startColumn}
+        : {}),
+      end_line: location.endLine,
+      path: location.path,
+      start_line: location.startLine,
+    }))
+  })
+}
+
+/**
+ * Render the check run summary: a table with the signals and files of each concern.
+ */
+export function renderCheckSummary(reports: ReportOutput[]): string {
+  if (reports.length === 0) {
+    return 'No signals fired.'
+  }
+
+  const groups = groupByConcern(reports)
+  const rows = [...groups].map(([concernId, concernReports]) => {
+    const files = new Set(concernReports.flatMap(({metadata}) => (metadata?.fileName ? [metadata.fileName] : [])))
+    const paths = [...files].map((file) => `\`${file}\``).join(', ')
+    return `| ${escapeCell(concernId)} | ${concernReports.length} | ${escapeCell(paths)} |`
+  })
+  const summary = [
+    `${countOf(reports.length, 'signal')} fired across ${countOf(groups.size, 'concern')}.`,
+    '',
+    '| Concern | Signals | Files |',
+    '| --- | ---: | --- |',
+    ...rows,
+  ].join('\n')
+
+  return summary.length <= MAX_SUMMARY_LENGTH ? summary : summary.slice(0, MAX_SUMMARY_LENGTH - 1) + '…'
+}
+
+/**
+ * Create the distill check run on a commit. GitHub shows the latest run of a given name,
+ * so a new run replaces the one from an earlier distill run on the same commit.
+ * Annotations beyond the per-request limit are sent in follow-up updates, which GitHub appends.
+ *
+ * @param conclusion - Conclusion to report when a `warning` or `error` signal fired
+ */
+export async function publishCheckRun(
+  octokit: Octokit,
+  repository: {owner: string; repo: string},
+  headSha: string,
+  reports: ReportOutput[],
+  conclusion: CheckConclusion,
+): Promise<CheckRunOutcome> {
+  const {owner, repo} = repository
+  const annotations = getCheckAnnotations(reports)
+  const batches: CheckAnnotation[][] = []
+  for (let start = 0; start < annotations.length; start += MAX_ANNOTATIONS_PER_REQUEST) {
+    batches.push(annotations.slice(start, start + MAX_ANNOTATIONS_PER_REQUEST))
+  }
+
+  const output = {
+    summary: renderCheckSummary(reports),
+    title: reports.length > 0 ? `${countOf(reports.length, 'signal')} fired` : 'No signals fired',
+  }
+  const runConclusion = getCheckConclusion(reports, conclusion)
+  const [first = [], ...rest] = batches
+
+  const {data} = await octokit.rest.checks.create({
+    conclusion: runConclusion,
+    head_sha: headSha,
+    name: CHECK_RUN_NAME,
+    output: {...output, annotations: first},
+    owner,
+    repo,
+    status: 'completed',
+  })
+
+  for (const batch of rest) {
+    // eslint-disable-next-line no-await-in-loop
+    await octokit.rest.checks.update({check_run_id: data.id, output: {...output, annotations: batch}, owner, repo})
+  }
+
+  return {annotations: annotations.length, conclusion: runConclusion, id: data.id}
+}
+
+function getCheckConclusion(reports: ReportOutput[], conclusion: CheckConclusion): CheckRunOutcome['conclusion'] {
+  if (reports.length === 0) return 'success'
+  const serious = reports.some((report) => meetsSeverity(report.metadata?.severity ?? 'info', 'warning'))
+  return serious ? conclusion : 'neutral'
+}
+
+function escapeCell(text: string): string {
+  return text.replaceAll('|', String.raw`\|`).replaceAll('\n', ' ')
+}
@@ -0,0 +1,137 @@
+import type {Octokit} from 'octokit'
+
+import type {ReportOutput} from '../reports/index.js'
+import type {PullRequestRef} from './stakeholders.js'
+
+import {countOf, groupByConcern} from '../notify/index.js'
+
+/** Hidden marker that identifies the distill comment on a pull request */
+export const COMMENT_MARKER = '<!-- distill:report -->'
+
+/** Hidden marker for a comment that was cleared because no signals fired */
+const RESOLVED_MARKER = '<!-- distill:resolved -->'
+
+/** GitHub rejects comment bodies longer than this */
+const MAX_COMMENT_LENGTH = 65_536
+
+const TRUNCATION_NOTICE = '\n\n_This comment was truncated. Run `distill pr` locally for the full report._'
+
+/**
+ * What to do with the distill comment when no signals fire.
+ * - `delete`: remove the comment
+ * - `collapse`: replace its body with a short note and hide it as outdated
+ */
+export type StaleCommentAction = 'collapse' | 'delete'
+
+/**
+ * What happened to the distill comment.
+ */
+export type CommentOutcome = 'collapsed' | 'created' | 'deleted' | 'none' | 'unchanged' | 'updated'
+
+/**
+ * Render reports as a comment body, grouped by concern.
+ */
+export function renderReportComment(reports: ReportOutput[]): string {
+  const groups = groupByConcern(reports)
+  const signals = countOf(reports.length, 'signal')
+  const concerns = countOf(groups.size, 'concern')
+  const sections = [...groups].map(([concernId, concernReports]) => {
+    const stakeholders = concernReports[0].metadata?.stakeholders?.map((stakeholder) => stakeholder.name) ?? []
+    const owners = stakeholders.length > 0 ? `\n\n_Stakeholders: ${stakeholders.join(', ')}_` : ''
+    return [`### ${concernId}${owners}`, ...concernReports.map((report) => report.content.trim())].join('\n\n')
+  })
+
+  const body = [COMMENT_MARKER, `## distill\n\n${signals} fired across ${concerns}.`, ...sections].join('\n\n')
+  if (body.length <= MAX_COMMENT_LENGTH) {
+    return body
+  }
+
+  return body.slice(0, MAX_COMMENT_LENGTH - TRUNCATION_NOTICE.length) + TRUNCATION_NOTICE
+}
+
+/**
+ * Create the distill comment on a pull request, or update it in place if a previous run posted one.
+ * A comment that was collapsed because nothing fired is expanded again.
+ */
+export async function upsertReportComment(
+  octokit: Octokit,
+  pr: PullRequestRef,
+  body: string,
+): Promise<CommentOutcome> {
+  const {number, owner, repo} = pr
+  const existing = await findMarkedComment(octokit, pr, COMMENT_MARKER)
+
+  if (!existing) {
+    /* eslint-disable camelcase */
+    await octokit.rest.issues.createComment({body, issue_number: number, owner, repo})
+    /* eslint-enable camelcase */
+    return 'created'
+  }
+
+  if (existing.body === body) {
+    return 'unchanged'
+  }
+
+  /* eslint-disable camelcase */
+  await octokit.rest.issues.updateComment({body, comment_id: existing.id, owner, repo})
+  /* eslint-enable camelcase */
+
+  if (existing.body?.includes(RESOLVED_MARKER)) {
+    await octokit.graphql('mutation($id: ID!) { unminimizeComment(input: {subjectId: $id}) { clientMutationId } }', {
+      id: existing.node_id,
+    })
+  }
+
+  return 'updated'
+}
+
+/**
+ * Delete or collapse the distill comment after the signals that produced it stop firing.
+ *
+ * @param headSha - Commit the pull request was checked at, mentioned in the collapsed comment
+ */
+export async function clearReportComment(
+  octokit: Octokit,
+  pr: PullRequestRef,
+  action: StaleCommentAction,
+  headSha: string,
+): Promise<CommentOutcome> {
+  const {owner, repo} = pr
+  const existing = await findMarkedComment(octokit, pr, COMMENT_MARKER)
+  if (!existing || existing.body?.includes(RESOLVED_MARKER)) {
+    return 'none'
+  }
+
+  if (action === 'delete') {
+    /* eslint-disable camelcase */
+    await octokit.rest.issues.deleteComment({comment_id: existing.id, owner, repo})
+    /* eslint-enable camelcase */
+    return 'deleted'
+  }
+
+  const body = [COMMENT_MARKER, RESOLVED_MARKER, `distill: no signals fire as of ${headSha}.`].join('\n')
+  /* eslint-disable camelcase */
+  await octokit.rest.issues.updateComment({body, comment_id: existing.id, owner, repo})
+  /* eslint-enable camelcase */
+  await octokit.graphql(
+    'mutation($id: ID!) { minimizeComment(input: {subjectId: $id, classifier: OUTDATED}) { clientMutationId } }',
+    {id: existing.node_id},
+  )
+  return 'collapsed'
+}
+
+/**
+ * Find the comment posted by a previous run through its hidden marker.
+ */
+export async function findMarkedComment(octokit: Octokit, pr: PullRequestRef, marker: string) {
+  const {number, owner, repo} = pr
+  /* eslint-disable camelcase */
+  const comments = await octokit.paginate(octokit.rest.issues.listComments, {
+    issue_number: number,
+    owner,
+    per_page: 100,
+    repo,
+  })
+  /* eslint-enable camelcase */
+  return comments.find((comment) => comment.body?.includes(marker))
+}
@@ -0,0 +1,5 @@
+export * from './check-run.js'
+export * from './comment.js'
+export * from './notify.js'
+export * from './review.js'
+export * from './stakeholders.js'
@@ -0,0 +1,64 @@
+import type {Octokit} from 'octokit'
+
+import type {Stakeholder} from '../configuration/config.js'
+import type {DeliveryResult} from '../notify/index.js'
+import type {ReportOutput} from '../reports/index.js'
+
+import {
+  type ContactOptions,
+  contactStakeholders,
+  normalizeHandle,
+  planContacts,
+  type PullRequestRef,
+} from './stakeholders.js'
+
+/**
+ * Collect the `notify.github` targets of fired signals as stakeholders to contact.
+ * A target that is also a stakeholder of the signal's concern is contacted per that
+ * stakeholder's `contactMethod`; any other target is asked for a review.
+ */
+export function getNotifyStakeholders(reports: ReportOutput[]): Stakeholder[] {
+  return reports.flatMap((report): Stakeholder[] => {
+    const target = report.notify?.github
+    if (!target) return []
+
+    const stakeholder = report.metadata?.stakeholders?.find(
+      ({github}) => github !== undefined && normalizeHandle(github) === normalizeHandle(target),
+    )
+    return [stakeholder ?? {contactMethod: 'github-reviewer-request', github: target, name: target}]
+  })
+}
+
+/**
+ * Contact the `notify.github` targets of fired signals on a pull request, as `getNotifyStakeholders` decides.
+ * Targets whose review is already requested, and the PR author, are skipped.
+ */
+export async function sendGithubNotifications(
+  octokit: Octokit,
+  pr: PullRequestRef,
+  targets: Map<string, ReportOutput[]>,
+  options: ContactOptions = {},
+): Promise<DeliveryResult[]> {
+  const plan = planContacts(getNotifyStakeholders([...targets.values()].flat()), options)
+  // Teams are requested by their slug, without the org
+  const key = (handle: string) => normalizeHandle(handle.split('/').at(-1)!)
+  const contacted = new Set(
+    [...plan.reviewers, ...plan.teamReviewers, ...plan.mentions.map(({handle}) => handle)].map((handle) => key(handle)),
+  )
+
+  let failure: string | undefined
+  try {
+    await contactStakeholders(octokit, pr, plan, 'notify')
+  } catch (error) {
+    failure = (error as Error).message
+  }
+
+  return [...targets].map(([target, reports]): DeliveryResult => {
+    const result: DeliveryResult = {channel: 'github', reports: reports.length, status: 'delivered', target}
+    if (!contacted.has(key(target))) {
+      return {...result, error: 'review already requested, or the pull request author', status: 'skipped'}
+    }
+
+    return failure ? {...result, error: failure, status: 'failed'} : result
+  })
+}
@@ -0,0 +1,162 @@
+import type {Octokit} from 'octokit'
+
+import type {File} from '../diff/parser.js'
+import type {ReportLocation, ReportOutput} from '../reports/index.js'
+import type {PullRequestRef} from './stakeholders.js'
+
+import {type ChangedLines, getChangedLines} from '../processing/scope.js'
+import {getReportFingerprint} from '../reports/index.js'
+
+const FINDING_MARKER = /<!-- distill:finding:(\w+) -->/
+
+/** Lines of a file shown in the diff, and the path GitHub knows the file by */
+interface DiffLines {
+  lines: ChangedLines
+  path: string
+}
+
+/**
+ * A review comment for one finding, on a line of the new (`RIGHT`) or old (`LEFT`) version of the file.
+ * Comments without a `line` are attached to the file rather than a line.
+ */
+export interface FindingComment {
+  body: string
+  fingerprint: string
+  line?: number
+  path: string
+  side?: 'LEFT' | 'RIGHT'
+}
+
+/**
+ * Review comments to post, and how many findings already have a comment from an earlier run.
+ */
+export interface ReviewPlan {
+  comments: FindingComment[]
+  duplicates: number
+}
+
+/**
+ * Decide where to comment on each finding.
+ * A finding is anchored on the first line of its changed locations that appears in the diff,
+ * preferring the new version of the file, and falls back to a file-level comment.
+ *
+ * @param existingFingerprints - Fingerprints of findings commented on by earlier runs
+ */
+export function planReviewComments(
+  reports: ReportOutput[],
+  files: File[],
+  existingFingerprints: Set<string>,
+): ReviewPlan {
+  const plan: ReviewPlan = {comments: [], duplicates: 0}
+  const seen = new Set(existingFingerprints)
+  // Review comments can only be anchored on lines shown in the diff, and only by the file's current name,
+  // even on the old side of a renamed file
+  const linesByPath = new Map<string, DiffLines>()
+  for (const file of files) {
+    const diffLines = {lines: getChangedLines(file, 'changed-hunks'), path: file.newPath}
+    linesByPath.set(file.newPath, diffLines)
+    if (file.oldPath !== file.newPath) linesByPath.set(file.oldPath, diffLines)
+  }
+
+  for (const report of reports) {
+    if (!report.metadata) continue
+    const fingerprint = getReportFingerprint(report.metadata)
+    if (seen.has(fingerprint)) {
+      plan.duplicates++
+      continue
+    }
+
+    seen.add(fingerprint)
+    const {fileName, locations} = report.metadata
+    const anchor =
+      findLine(locations?.new ?? [], linesByPath, 'new') ?? findLine(locations?.old ?? [], linesByPath, 'old')
+
+    plan.comments.push({
+      body: `${report.content.trim()}\n\n<!-- distill:finding:${fingerprint} -->`,
+      fingerprint,
+      path: anchor?.path ?? fileName,
+      ...(anchor ? {line: anchor.line, side: anchor.side} : {}),
+    })
+  }
+
+  return plan
+}
+
+/**
+ * Post a review with an inline comment per new finding, plus file-level comments
+ * for findings outside the diff. Findings commented on by earlier runs are skipped.
+ *
+ * @param headSha - Commit the new side of the diff refers to
+ */
+export async function postReview(
+  octokit: Octokit,
+  pr: PullRequestRef,
+  headSha: string,
+  reports: ReportOutput[],
+  files: File[],
+): Promise<ReviewPlan> {
+  const {number, owner, repo} = pr
+
+  /* eslint-disable camelcase */
+  const existing = await octokit.paginate(octokit.rest.pulls.listReviewComments, {
+    owner,
+    per_page: 100,
+    pull_number: number,
+    repo,
+  })
+  /* eslint-enable camelcase */
+  const existingFingerprints = new Set(
+    existing.map((comment) => FINDING_MARKER.exec(comment.body)?.[1]).filter((id): id is string => id !== undefined),
+  )
+
+  const plan = planReviewComments(reports, files, existingFingerprints)
+  const inline = plan.comments.filter((comment) => comment.line !== undefined)
+  const fileLevel = plan.comments.filter((comment) => comment.line === undefined)
+
+  if (inline.length > 0) {
+    /* eslint-disable camelcase */
+    await octokit.rest.pulls.createReview({
+      body: `distill found ${plan.comments.length} new ${plan.comments.length === 1 ? 'finding' : 'findings'}.`,
+      comments: inline.map(({body, line, path, side}) => ({body, line, path, side})),
+      commit_id: headSha,
+      event: 'COMMENT',
+      owner,
+      pull_number: number,
+      repo,
+    })
+    /* eslint-enable camelcase */
+  }
+
+  for (const {body, path} of fileLevel) {
+    /* eslint-disable camelcase */
+    // eslint-disable-next-line no-await-in-loop
+    await octokit.rest.pulls.createReviewComment({
+      body,
+      commit_id: headSha,
+      owner,
+      path,
+      pull_number: number,
+      repo,
+      subject_type: 'file',
+    })
+    /* eslint-enable camelcase */
+  }
+
+  return plan
+}
+
+function findLine(
+  locations: ReportLocation[],
+  linesByPath: Map<string, DiffLines>,
+  version: keyof ChangedLines,
+): undefined | {line: number; path: string; side: 'LEFT' | 'RIGHT'} {
+  for (const location of locations) {
+    const file = linesByPath.get(location.path)
+    const lines = file?.lines[version]
+    for (let line = location.startLine; file && lines && line <= location.endLine; line++) {
+      if (lines.has(line)) return {line, path: file.path, side: version === 'new' ? 'RIGHT' : 'LEFT'}
+    }
+  }
+
+  return undefined
+}
@@ -0,0 +1,196 @@
+import type {Octokit} from 'octokit'
+
+import type {Stakeholder} from '../configuration/config.js'
+
+import {findMarkedComment} from './comment.js'
+
+/**
+ * Pull request coordinates.
+ */
+export interface PullRequestRef {
+  number: number
+  owner: string
+  repo: string
+}
+
+/**
+ * What to do on a pull request to reach the stakeholders of fired signals.
+ */
+export interface StakeholderContactPlan {
+  /** Reviewers and team slugs skipped because their review is already requested */
+  alreadyRequested: string[]
+  /** Stakeholders to mention in a comment, with their `@handle` */
+  mentions: Array<{handle: string; stakeholder: Stakeholder}>
+  /** User logins to request reviews from */
+  reviewers: string[]
+  /** Team slugs to request reviews from */
+  teamReviewers: string[]
+  /** Stakeholders with a github-* contact method but no `github` handle */
+  unreachable: Stakeholder[]
+}
+
+/**
+ * Pull request state that decides whom not to request reviews from.
+ */
+export interface ContactOptions {
+  /** PR author login, who cannot be requested as a reviewer */
+  author?: string
+  /** Logins of users whose review is already requested */
+  requestedReviewers?: string[]
+  /** Slugs of teams whose review is already requested */
+  requestedTeams?: string[]
+}
+
+/**
+ * Decide how to contact each stakeholder, skipping the PR author and reviewers whose review
+ * is already requested. Each stakeholder is contacted once.
+ */
+export function planContacts(stakeholders: Stakeholder[], options: ContactOptions = {}): StakeholderContactPlan {
+  const plan: StakeholderContactPlan = {
+    alreadyRequested: [],
+    mentions: [],
+    reviewers: [],
+    teamReviewers: [],
+    unreachable: [],
+  }
+  const requestedReviewers = new Set(options.requestedReviewers?.map((login) => normalizeHandle(login)))
+  const requestedTeams = new Set(options.requestedTeams?.map((slug) => normalizeHandle(slug)))
+  const seen = new Set<string>()
+
+  for (const stakeholder of stakeholders) {
+    const contact = stakeholder.github ? normalizeHandle(stakeholder.github) : stakeholder.name
+    const key = `${stakeholder.contactMethod}:${contact}`
+    if (seen.has(key)) continue
+    seen.add(key)
+
+    if (!stakeholder.github) {
+      plan.unreachable.push(stakeholder)
+      continue
+    }
+
+    const handle = stakeholder.github.replace(/^@/, '')
+
+    switch (stakeholder.contactMethod) {
+      case 'github-comment-mention': {
+        plan.mentions.push({handle: `@${handle}`, stakeholder})
+        break
+      }
+
+      case 'github-reviewer-request': {
+        if (handle.includes('/')) {
+          // The review request API takes the team slug without its org
+          const slug = handle.split('/')[1]
+          if (requestedTeams.has(normalizeHandle(slug))) {
+            plan.alreadyRequested.push(`@${handle}`)
+          } else {
+            plan.teamReviewers.push(slug)
+          }
+        } else if (requestedReviewers.has(normalizeHandle(handle))) {
+          plan.alreadyRequested.push(`@${handle}`)
+        } else if (normalizeHandle(handle) !== normalizeHandle(options.author ?? '')) {
+          plan.reviewers.push(handle)
+        }
+
+        break
+      }
+    }
+  }
+
+  return plan
+}
+
+/**
+ * Compare GitHub handles regardless of a leading `@` and case.
+ */
+export function normalizeHandle(handle: string): string {
+  return handle.replace(/^@/, '').toLowerCase()
+}
+
+/**
+ * Describe what a contact plan would do, one line per action.
+ */
+export function describeContactPlan(plan: StakeholderContactPlan): string[] {
+  const lines: string[] = []
+  if (plan.reviewers.length > 0) {
+    lines.push(`Would request reviews from ${plan.reviewers.map((login) => `@${login}`).join(', ')}`)
+  }
+
+  if (plan.teamReviewers.length > 0) {
+    lines.push(`Would request reviews from teams ${plan.teamReviewers.join(', ')}`)
+  }
+
+  if (plan.mentions.length > 0) {
+    lines.push(`Would mention ${plan.mentions.map(({handle}) => handle).join(', ')} in a comment`)
+  }
+
+  if (plan.alreadyRequested.length > 0) {
+    lines.push(`Would skip ${plan.alreadyRequested.join(', ')}, whose review is already requested`)
+  }
+
+  return lines.length > 0 ? lines : ['No one to contact']
+}
+
+/**
+ * Which mention comment a contact plan keeps up to date. Stakeholders of fired concerns and
+ * `notify.github` targets each get their own, so contacting one does not drop the other's mentions.
+ */
+export type MentionComment = 'notify' | 'stakeholders'
+
+/**
+ * Hidden marker that identifies a mention comment, so later runs update it instead of mentioning everyone again.
+ */
+export function mentionMarker(comment: MentionComment): string {
+  return `<!-- distill:mentions:${comment} -->`
+}
+
+/**
+ * Render the comment used to mention stakeholders.
+ */
+export function renderMentionComment(
+  mentions: StakeholderContactPlan['mentions'],
+  comment: MentionComment = 'stakeholders',
+): string {
+  const lines = mentions.map(({handle, stakeholder}) => {
+    const description = stakeholder.description ? `: ${stakeholder.description}` : ''
+    return `- ${handle} (${stakeholder.name}${description})`
+  })
+  return [mentionMarker(comment), 'distill found changes relevant to:', '', ...lines].join('\n')
+}
+
+/**
+ * Carry out a contact plan on a pull request.
+ * Mentions go in one comment that later runs update, and leave alone while the mentions are unchanged,
+ * so stakeholders are not pinged again on every push.
+ */
+export async function contactStakeholders(
+  octokit: Octokit,
+  pr: PullRequestRef,
+  plan: StakeholderContactPlan,
+  comment: MentionComment = 'stakeholders',
+): Promise<void> {
+  const {number, owner, repo} = pr
+
+  if (plan.reviewers.length > 0 || plan.teamReviewers.length > 0) {
+    /* eslint-disable camelcase */
+    await octokit.rest.pulls.requestReviewers({
+      owner,
+      pull_number: number,
+      repo,
+      reviewers: plan.reviewers,
+      team_reviewers: plan.teamReviewers,
+    })
+    /* eslint-enable camelcase */
+  }
+
+  if (plan.mentions.length > 0) {
+    const body = renderMentionComment(plan.mentions, comment)
+    const existing = await findMarkedComment(octokit, pr, mentionMarker(comment))
+    /* eslint-disable camelcase */
+    if (!existing) {
+      await octokit.rest.issues.createComment({body, issue_number: number, owner, repo})
+    } else if (existing.body !== body) {
+      await octokit.rest.issues.updateComment({body, comment_id: existing.id, owner, repo})
+    }
+    /* eslint-enable camelcase */
+  }
+}
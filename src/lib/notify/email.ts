@@ -0,0 +1,104 @@
+import Handlebars from 'handlebars'
+import nodemailer from 'nodemailer'
+
+import type {EmailNotifierConfig} from '../configuration/config.js'
+import type {ReportOutput} from '../reports/index.js'
+import type {DeliveryResult, NotifySource} from './index.js'
+
+import {createTemplateData, skipTargets} from './index.js'
+
+const TEXT_TEMPLATE = Handlebars.compile(
+  `{{title}}
+{{origin}}
+{{#each concerns}}
+
+== {{concernId}} ==
+{{#each reports}}
+
+{{file}}
+{{content}}
+{{/each}}
+{{/each}}
+`,
+  {noEscape: true},
+)
+
+const HTML_TEMPLATE = Handlebars.compile(`<!doctype html>
+<html>
+<body>
+<h1>{{title}}</h1>
+<p>{{#if pullRequest}}<a href="{{pullRequest.url}}">{{origin}}</a>{{else}}{{origin}}{{/if}}</p>
+{{#each concerns}}
+<h2>{{concernId}}</h2>
+{{#each reports}}
+<h3><code>{{file}}</code></h3>
+<pre>{{content}}</pre>
+{{/each}}
+{{/each}}
+</body>
+</html>
+`)
+
+/**
+ * An email with plain text and HTML parts.
+ */
+export interface EmailDigest {
+  html: string
+  subject: string
+  text: string
+}
+
+/**
+ * Render the digest for one recipient: every report addressed to them, grouped by concern.
+ */
+export function renderEmailDigest(reports: ReportOutput[], source: NotifySource): EmailDigest {
+  const data = createTemplateData(reports, source)
+  return {html: HTML_TEMPLATE(data), subject: `${data.title} in ${data.origin}`, text: TEXT_TEMPLATE(data)}
+}
+
+/**
+ * Send one digest per recipient over SMTP.
+ * Settings fall back to `SMTP_*` environment variables; email is skipped when no host is set.
+ */
+export async function sendEmailNotifications(
+  targets: Map<string, ReportOutput[]>,
+  settings: EmailNotifierConfig,
+  source: NotifySource,
+  env: NodeJS.ProcessEnv = process.env,
+): Promise<DeliveryResult[]> {
+  const host = settings.host ?? env.SMTP_HOST
+  if (!host) {
+    return skipTargets(targets, 'email', 'Set notifiers.email.host or SMTP_HOST to send email notifications')
+  }
+
+  const secure = settings.secure ?? env.SMTP_SECURE === 'true'
+  const defaultPort = secure ? 465 : 587
+  const port = settings.port ?? (env.SMTP_PORT ? Number(env.SMTP_PORT) : defaultPort)
+  const from = settings.from ?? env.SMTP_FROM ?? env.SMTP_USER ?? 'distill@localhost'
+  const transport = nodemailer.createTransport({
+    ...(env.SMTP_USER ? {auth: {pass: env.SMTP_PASSWORD, user: env.SMTP_USER}} : {}),
+    host,
+    port,
+    secure,
+  })
+
+  const results: DeliveryResult[] = []
+  try {
+    for (const [target, reports] of targets) {
+      const result: DeliveryResult = {channel: 'email', reports: reports.length, status: 'delivered', target}
+      try {
+        // eslint-disable-next-line no-await-in-loop
+        await transport.sendMail({...renderEmailDigest(reports, source), from, to: target})
+      } catch (error) {
+        result.status = 'failed'
+        result.error = (error as Error).message
+      }
+
+      results.push(result)
+    }
+  } finally {
+    transport.close()
+  }
+
+  return results
+}
@@ -0,0 +1,90 @@
+/**
+ * How to retry a request that failed.
+ */
+export interface RetryOptions {
+  /** Milliseconds to wait before the first retry, doubled for each retry after it */
+  backoff: number
+  /** How many times to retry after the first attempt */
+  retries: number
+  /** Milliseconds to wait for a response before giving up on an attempt */
+  timeout: number
+}
+
+/**
+ * Thrown when a request still fails after its retries, with the last status if there was a response.
+ */
+export class DeliveryError extends Error {
+  constructor(
+    message: string,
+    public readonly status?: number,
+  ) {
+    super(message)
+    this.name = 'DeliveryError'
+  }
+}
+
+/**
+ * Send a request, retrying network errors, timeouts, rate limits (429) and server errors (5xx)
+ * with exponential backoff. A rate-limited response's `Retry-After` header takes precedence over the backoff.
+ *
+ * @throws DeliveryError if the request fails after all retries, or with a status that is not retried
+ */
+export async function fetchWithRetry(url: string, init: RequestInit, options: RetryOptions): Promise<Response> {
+  let lastError = ''
+  let lastStatus: number | undefined
+
+  for (let attempt = 0; attempt <= options.retries; attempt++) {
+    let delay = options.backoff * 2 ** attempt
+    try {
+      // eslint-disable-next-line no-await-in-loop
+      const response = await fetch(url, {...init, signal: AbortSignal.timeout(options.timeout)})
+      if (response.ok) {
+        return response
+      }
+
+      lastStatus = response.status
+      // eslint-disable-next-line no-await-in-loop
+      lastError = `${response.status} ${response.statusText}: ${(await response.text()).slice(0, 200)}`.trim()
+      if (response.status !== 429 && response.status < 500) {
+        break
+      }
+
+      const retryAfter = Number.parseInt(response.headers.get('retry-after') ?? '', 10)
+      if (response.status === 429 && retryAfter >= 0) {
+        delay = retryAfter * 1000
+      }
+    } catch (error) {
+      lastStatus = undefined
+      lastError = (error as Error).name === 'TimeoutError' ? `timed out after ${options.timeout}ms` : String(error)
+    }
+
+    if (attempt < options.retries) {
+      // eslint-disable-next-line no-await-in-loop
+      await sleep(delay)
+    }
+  }
+
+  throw new DeliveryError(lastError, lastStatus)
+}
+
+/**
+ * Create a function that resolves once at least `interval` milliseconds have passed since it last resolved,
+ * to space out requests to rate-limited APIs.
+ */
+export function createRateLimiter(interval: number): () => Promise<void> {
+  let next = 0
+  return async () => {
+    const now = Date.now()
+    const wait = Math.max(0, next - now)
+    next = Math.max(now, next) + interval
+    if (wait > 0) {
+      await sleep(wait)
+    }
+  }
+}
+
+export function sleep(milliseconds: number): Promise<void> {
+  return new Promise((resolve) => {
+    setTimeout(resolve, milliseconds)
+  })
+}
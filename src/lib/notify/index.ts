@@ -0,0 +1,217 @@
+import type {NotifiersConfig, NotifyConfig} from '../configuration/config.js'
+import type {ReportOutput} from '../reports/index.js'
+
+import {getReportFingerprint} from '../reports/index.js'
+import {sendEmailNotifications} from './email.js'
+import {sendJiraNotifications} from './jira.js'
+import {sendSlackNotifications} from './slack.js'
+import {sendWebhookNotifications} from './webhook.js'
+
+/**
+ * A notify channel, like `slack` or `email`.
+ */
+export type NotifyChannel = keyof NotifyConfig
+
+/**
+ * Channels delivered by `sendNotifications`, in the order they are sent.
+ */
+export const DELIVERED_CHANNELS = ['email', 'github', 'jira', 'slack', 'webhook'] as const satisfies NotifyChannel[]
+
+/**
+ * Where the reports of a run came from, for links and context in notifications.
+ */
+export interface NotifySource {
+  /** Pull request the run checked, when running `distill pr` */
+  pullRequest?: {number: number; url: string}
+  refs: {base: string; head: string}
+  /** Repository as `owner/repo`, when known */
+  repository?: string
+}
+
+/**
+ * What happened to the notification for one channel target.
+ * - `delivered`: the notification was sent
+ * - `failed`: sending failed; `error` says why
+ * - `skipped`: the channel is not set up, so nothing was sent
+ */
+export interface DeliveryResult {
+  channel: NotifyChannel
+  error?: string
+  /** How many reports the notification carried */
+  reports: number
+  status: 'delivered' | 'failed' | 'skipped'
+  target: string
+}
+
+/**
+ * Contacts `notify.github` targets on the pull request `distill pr` checks, one result per target.
+ */
+export type GithubNotifier = (targets: Map<string, ReportOutput[]>) => Promise<DeliveryResult[]>
+
+/**
+ * A notification `sendNotifications` would deliver, with the fingerprints of the reports it carries.
+ */
+export interface PlannedDelivery {
+  channel: NotifyChannel
+  reports: string[]
+  target: string
+}
+
+/**
+ * The data notification templates are rendered with: the reports of one target, grouped by concern.
+ */
+export interface NotificationTemplateData {
+  concerns: Array<{concernId: string; reports: Array<{content: string; file: string; severity?: string}>}>
+  origin: string
+  pullRequest?: {number: number; url: string}
+  refs: {base: string; head: string}
+  repository?: string
+  title: string
+}
+
+/**
+ * Count a noun, like `1 signal` or `3 signals`.
+ */
+export function countOf(count: number, noun: string): string {
+  return `${count} ${count === 1 ? noun : `${noun}s`}`
+}
+
+/**
+ * Build the template data for the reports addressed to one target.
+ */
+export function createTemplateData(reports: ReportOutput[], source: NotifySource): NotificationTemplateData {
+  const concerns = [...groupByConcern(reports)].map(([concernId, concernReports]) => ({
+    concernId,
+    reports: concernReports.map((report) => ({
+      content: report.content.trim(),
+      file: report.metadata?.fileName ?? '',
+      ...(report.metadata?.severity ? {severity: report.metadata.severity} : {}),
+    })),
+  }))
+
+  return {
+    concerns,
+    origin: describeOrigin(source),
+    ...(source.pullRequest ? {pullRequest: source.pullRequest} : {}),
+    refs: source.refs,
+    ...(source.repository ? {repository: source.repository} : {}),
+    title: getNotificationTitle(reports),
+  }
+}
+
+/**
+ * Describe where the reports came from, like `owner/repo pull request #12` or `main..feature`.
+ */
+export function describeOrigin(source: NotifySource): string {
+  const where = source.pullRequest
+    ? `pull request #${source.pullRequest.number}`
+    : `${source.refs.base}..${source.refs.head}`
+  return source.repository ? `${source.repository} ${where}` : where
+}
+
+/**
+ * Title of a notification, like `distill: 2 signals fired`.
+ */
+export function getNotificationTitle(reports: ReportOutput[]): string {
+  return `distill: ${countOf(reports.length, 'signal')} fired`
+}
+
+/**
+ * Group reports by concern, in the order concerns first fire. Reports without one go under `other`.
+ */
+export function groupByConcern(reports: ReportOutput[]): Map<string, ReportOutput[]> {
+  const groups = new Map<string, ReportOutput[]>()
+  for (const report of reports) {
+    const concernId = report.metadata?.concernId ?? 'other'
+    groups.set(concernId, [...(groups.get(concernId) ?? []), report])
+  }
+
+  return groups
+}
+
+/**
+ * Group reports by their target on a notify channel, so each target gets one notification.
+ */
+export function groupByTarget(reports: ReportOutput[], channel: NotifyChannel): Map<string, ReportOutput[]> {
+  const groups = new Map<string, ReportOutput[]>()
+  for (const report of reports) {
+    const target = report.notify?.[channel]
+    if (target) {
+      groups.set(target, [...(groups.get(target) ?? []), report])
+    }
+  }
+
+  return groups
+}
+
+/**
+ * List the notifications `sendNotifications` would deliver for these reports, without sending anything.
+ */
+export function planNotifications(reports: ReportOutput[]): PlannedDelivery[] {
+  return DELIVERED_CHANNELS.flatMap((channel) =>
+    [...groupByTarget(reports, channel)].map(
+      ([target, targetReports]): PlannedDelivery => {
+        const fingerprints = targetReports.flatMap((report) =>
+          report.metadata ? [getReportFingerprint(report.metadata)] : [],
+        )
+        return {channel, reports: [...new Set(fingerprints)], target}
+      },
+    ),
+  )
+}
+
+/**
+ * Deliver the notify targets of fired signals.
+ * `github` targets are contacted through `github`, which `distill pr` passes for the pull request it checks,
+ * and skipped without it.
+ */
+export async function sendNotifications(
+  reports: ReportOutput[],
+  notifiers: NotifiersConfig,
+  source: NotifySource,
+  github?: GithubNotifier,
+): Promise<DeliveryResult[]> {
+  const results: DeliveryResult[] = []
+
+  const email = groupByTarget(reports, 'email')
+  if (email.size > 0) {
+    results.push(...(await sendEmailNotifications(email, notifiers.email ?? {}, source)))
+  }
+
+  const githubTargets = groupByTarget(reports, 'github')
+  if (githubTargets.size > 0 && github) {
+    results.push(...(await github(githubTargets)))
+  } else {
+    results.push(...skipTargets(githubTargets, 'github', 'GitHub targets are only contacted by distill pr'))
+  }
+
+  const jira = groupByTarget(reports, 'jira')
+  if (jira.size > 0) {
+    results.push(...(await sendJiraNotifications(jira, notifiers.jira ?? {}, source)))
+  }
+
+  const slack = groupByTarget(reports, 'slack')
+  if (slack.size > 0) {
+    results.push(...(await sendSlackNotifications(slack, notifiers.slack ?? {}, source)))
+  }
+
+  const webhooks = groupByTarget(reports, 'webhook')
+  if (webhooks.size > 0) {
+    results.push(...(await sendWebhookNotifications(webhooks, notifiers.webhook ?? {}, source)))
+  }
+
+  return results
+}
+
+/**
+ * Skip every target of a channel that is not set up, with the same reason for each.
+ */
+export function skipTargets(
+  targets: Map<string, ReportOutput[]>,
+  channel: NotifyChannel,
+  error: string,
+): DeliveryResult[] {
+  return [...targets].map(
+    ([target, reports]): DeliveryResult => ({channel, error, reports: reports.length, status: 'skipped', target}),
+  )
+}
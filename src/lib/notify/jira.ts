@@ -0,0 +1,169 @@
+import Handlebars from 'handlebars'
+import {createHash} from 'node:crypto'
+
+import type {JiraNotifierConfig} from '../configuration/config.js'
+import type {ReportOutput} from '../reports/index.js'
+import type {DeliveryResult, NotificationTemplateData, NotifySource} from './index.js'
+
+import {DeliveryError, fetchWithRetry} from './http.js'
+import {createTemplateData, skipTargets} from './index.js'
+
+const ISSUE_KEY = /^[A-Z][\dA-Z_]*-\d+$/
+const PROJECT_KEY = /^[A-Z][\dA-Z_]*$/
+
+const DEFAULT_SUMMARY = '{{title}} in {{origin}}'
+
+const DEFAULT_DESCRIPTION = `{{#if pullRequest}}[{{origin}}|{{pullRequest.url}}]{{else}}{{origin}}{{/if}}
+{{#each concerns}}
+
+h3. {{concernId}}
+{{#each reports}}
+
+*{{file}}*
+{noformat}
+{{content}}
+{noformat}
+{{/each}}
+{{/each}}
+`
+
+/**
+ * The fields of an issue that can hold a fingerprint.
+ */
+interface JiraIssue {
+  fields: {comment?: {comments: Array<{body: string}>}; description?: null | string}
+}
+
+/**
+ * Labels for an issue created for these reports: `distill`, and `distill-<concern>` for each concern.
+ */
+export function getJiraLabels(reports: ReportOutput[]): string[] {
+  const concernIds = reports.map((report) => report.metadata?.concernId).filter((id): id is string => id !== undefined)
+  return ['distill', ...new Set(concernIds.map((id) => `distill-${id.replaceAll(/[^\w-]+/g, '-')}`))]
+}
+
+/**
+ * Hash the parts that identify a notification, to recognize it on later runs.
+ */
+export function jiraFingerprint(...parts: string[]): string {
+  return createHash('sha256').update(JSON.stringify(parts)).digest('hex').slice(0, 16)
+}
+
+/**
+ * Comment on each `notify.jira` issue key, or create an issue in each project key.
+ * Created issues are labelled with a fingerprint of the pull request (or refs without one), so later runs
+ * comment on the same issue instead of creating another. Comments and descriptions end with a fingerprint of
+ * their reports, and reports that were already posted to an issue are not posted again.
+ * Jira is skipped when no site URL or `JIRA_API_TOKEN` is set.
+ */
+export async function sendJiraNotifications(
+  targets: Map<string, ReportOutput[]>,
+  settings: JiraNotifierConfig,
+  source: NotifySource,
+  env: NodeJS.ProcessEnv = process.env,
+): Promise<DeliveryResult[]> {
+  const url = settings.url ?? env.JIRA_URL
+  const token = env.JIRA_API_TOKEN
+  if (!url || !token) {
+    const error = 'Set notifiers.jira.url or JIRA_URL, and JIRA_API_TOKEN to send Jira notifications'
+    return skipTargets(targets, 'jira', error)
+  }
+
+  const authorization = env.JIRA_EMAIL
+    ? `Basic ${Buffer.from(`${env.JIRA_EMAIL}:${token}`).toString('base64')}`
+    : `Bearer ${token}`
+  const request = async <T>(path: string, init: RequestInit = {}, retries = 3): Promise<T> => {
+    const response = await fetchWithRetry(
+      `${url.replace(/\/+$/, '')}/rest/api/2${path}`,
+      {
+        ...init,
+        headers: {accept: 'application/json', authorization, 'content-type': 'application/json'},
+      },
+      {backoff: 1000, retries, timeout: 10_000},
+    )
+    return (await response.json()) as T
+  }
+
+  // Jira Cloud searches at /search/jql; Jira Server and Data Center only have /search
+  let searchPath = '/search/jql'
+  const findIssue = async (jql: string): Promise<string | undefined> => {
+    const query = `?jql=${encodeURIComponent(jql)}&fields=key&maxResults=1`
+    try {
+      const found = await request<{issues: Array<{key: string}>}>(`${searchPath}${query}`)
+      return found.issues[0]?.key
+    } catch (error) {
+      if (searchPath === '/search' || !(error instanceof DeliveryError) || error.status !== 404) {
+        throw error
+      }
+
+      searchPath = '/search'
+      return findIssue(jql)
+    }
+  }
+
+  const summaryTemplate = Handlebars.compile(settings.summary ?? DEFAULT_SUMMARY, {noEscape: true})
+  const descriptionTemplate = Handlebars.compile(settings.description ?? DEFAULT_DESCRIPTION, {noEscape: true})
+  const changes = source.pullRequest ? `#${source.pullRequest.number}` : `${source.refs.base}..${source.refs.head}`
+  const createIssue = async (
+    project: string,
+    labels: string[],
+    data: NotificationTemplateData,
+    description: string,
+  ) => {
+    const fields = {
+      description,
+      issuetype: {name: settings.issueType ?? 'Task'},
+      labels,
+      project: {key: project},
+      summary: summaryTemplate(data).trim().slice(0, 255),
+    }
+    // Not retried: an attempt that timed out or failed with a server error may still have created the issue,
+    // and the next run finds that one by its fingerprint label instead of creating another
+    const created = await request<{key: string}>('/issue', {body: JSON.stringify({fields}), method: 'POST'}, 0)
+    return created.key
+  }
+
+  const results: DeliveryResult[] = []
+
+  for (const [target, reports] of targets) {
+    const result: DeliveryResult = {channel: 'jira', reports: reports.length, status: 'delivered', target}
+    const data = createTemplateData(reports, source)
+    const fingerprint = jiraFingerprint(source.repository ?? '', changes, target, ...reports.map(reportKey))
+    const body = `${descriptionTemplate(data).trim()}\n\n_distill fingerprint: ${fingerprint}_`
+
+    try {
+      let issueKey = target
+      if (PROJECT_KEY.test(target)) {
+        const label = `distill-fp-${jiraFingerprint(source.repository ?? '', changes, target)}`
+        const jql = `project = "${target}" AND labels = "${label}" ORDER BY created ASC`
+        // eslint-disable-next-line no-await-in-loop
+        issueKey = (await findIssue(jql)) ?? (await createIssue(target, [...getJiraLabels(reports), label], data, body))
+      } else if (!ISSUE_KEY.test(target)) {
+        throw new Error('expected an issue key like SEC-123 or a project key like SEC')
+      }
+
+      // eslint-disable-next-line no-await-in-loop
+      const {fields} = await request<JiraIssue>(`/issue/${encodeURIComponent(issueKey)}?fields=comment,description`)
+      const posted = [fields.description ?? '', ...(fields.comment?.comments ?? []).map((comment) => comment.body)]
+      if (!posted.some((text) => text.includes(fingerprint))) {
+        // eslint-disable-next-line no-await-in-loop
+        await request(`/issue/${encodeURIComponent(issueKey)}/comment`, {body: JSON.stringify({body}), method: 'POST'})
+      }
+    } catch (error) {
+      result.status = 'failed'
+      result.error = (error as Error).message
+    }
+
+    results.push(result)
+  }
+
+  return results
+}
+
+/**
+ * Identify a report by what it found, for the fingerprint of a notification.
+ */
+function reportKey(report: ReportOutput): string {
+  const {metadata} = report
+  return JSON.stringify([metadata?.concernId, metadata?.signalId, metadata?.fileName, report.content])
+}
@@ -0,0 +1,149 @@
+import type {SlackNotifierConfig} from '../configuration/config.js'
+import type {ReportOutput} from '../reports/index.js'
+import type {DeliveryResult, NotifySource} from './index.js'
+
+import {createRateLimiter, fetchWithRetry} from './http.js'
+import {getNotificationTitle, skipTargets} from './index.js'
+
+const DEFAULT_API_URL = 'https://slack.com/api'
+
+/** Slack rejects messages with more blocks than this */
+const MAX_BLOCKS = 50
+
+/** Slack rejects section text longer than this */
+const MAX_SECTION_LENGTH = 3000
+
+/**
+ * Where to post Slack messages: with a bot token to any channel, or to an incoming webhook's channel.
+ */
+export interface SlackCredentials {
+  token?: string
+  webhookUrl?: string
+}
+
+/**
+ * A Block Kit block, as far as distill uses them.
+ */
+export type SlackBlock =
+  | {elements: Array<{text: string; type: 'mrkdwn'}>; type: 'context'}
+  | {text: {text: string; type: 'mrkdwn'}; type: 'section'}
+  | {text: {text: string; type: 'plain_text'}; type: 'header'}
+
+/**
+ * A Slack message with Block Kit blocks and a plain text fallback for notifications.
+ */
+export interface SlackMessage {
+  blocks: SlackBlock[]
+  text: string
+}
+
+/**
+ * Render the message for one channel: a header, where the changes come from, and a section per report
+ * with its concern, file and rendered content.
+ */
+export function renderSlackMessage(reports: ReportOutput[], source: NotifySource): SlackMessage {
+  const text = getNotificationTitle(reports)
+  const origin = [
+    ...(source.repository ? [`*${escapeMrkdwn(source.repository)}*`] : []),
+    source.pullRequest
+      ? `<${source.pullRequest.url}|Pull request #${source.pullRequest.number}>`
+      : `\`${escapeMrkdwn(source.refs.base)}\`..\`${escapeMrkdwn(source.refs.head)}\``,
+  ].join(' · ')
+
+  const blocks: SlackBlock[] = [
+    {text: {text, type: 'plain_text'}, type: 'header'},
+    {elements: [{text: origin, type: 'mrkdwn'}], type: 'context'},
+  ]
+
+  // Leave room for the note about reports that did not fit
+  const shown = reports.length > MAX_BLOCKS - blocks.length ? reports.slice(0, MAX_BLOCKS - blocks.length - 1) : reports
+  for (const report of shown) {
+    const concernId = escapeMrkdwn(report.metadata?.concernId ?? 'other')
+    const heading = `*${concernId}* · \`${escapeMrkdwn(report.metadata?.fileName ?? '')}\``
+    const section = `${heading}\n${escapeMrkdwn(report.content.trim())}`
+    blocks.push({
+      text: {
+        text: section.length <= MAX_SECTION_LENGTH ? section : `${section.slice(0, MAX_SECTION_LENGTH - 1)}…`,
+        type: 'mrkdwn',
+      },
+      type: 'section',
+    })
+  }
+
+  if (shown.length < reports.length) {
+    const more = `…and ${reports.length - shown.length} more. Run distill locally for the full report.`
+    blocks.push({elements: [{text: more, type: 'mrkdwn'}], type: 'context'})
+  }
+
+  return {blocks, text}
+}
+
+/**
+ * Post a message per Slack target, spaced out to respect rate limits.
+ * Targets are channels like `#security-alerts` when posting with a bot token. An incoming webhook
+ * posts to its own channel, so each target still gets its own message there.
+ * Slack is skipped when neither `SLACK_BOT_TOKEN` nor `SLACK_WEBHOOK_URL` is set.
+ */
+export async function sendSlackNotifications(
+  targets: Map<string, ReportOutput[]>,
+  settings: SlackNotifierConfig,
+  source: NotifySource,
+  credentials: SlackCredentials = {token: process.env.SLACK_BOT_TOKEN, webhookUrl: process.env.SLACK_WEBHOOK_URL},
+): Promise<DeliveryResult[]> {
+  const {token, webhookUrl} = credentials
+  if (!token && !webhookUrl) {
+    return skipTargets(targets, 'slack', 'Set SLACK_BOT_TOKEN or SLACK_WEBHOOK_URL to send Slack notifications')
+  }
+
+  const waitTurn = createRateLimiter(settings.interval ?? 1000)
+  const retryOptions = {backoff: 1000, retries: settings.retries ?? 3, timeout: 10_000}
+  const results: DeliveryResult[] = []
+
+  for (const [target, reports] of targets) {
+    const message = renderSlackMessage(reports, source)
+    const result: DeliveryResult = {channel: 'slack', reports: reports.length, status: 'delivered', target}
+    try {
+      // eslint-disable-next-line no-await-in-loop
+      await waitTurn()
+      if (token) {
+        // eslint-disable-next-line no-await-in-loop
+        const response = await fetchWithRetry(
+          `${settings.apiUrl ?? DEFAULT_API_URL}/chat.postMessage`,
+          {
+            body: JSON.stringify({...message, channel: target}),
+            headers: {authorization: `Bearer ${token}`, 'content-type': 'application/json; charset=utf-8'},
+            method: 'POST',
+          },
+          retryOptions,
+        )
+        // The Web API reports most errors with a 200 response
+        // eslint-disable-next-line no-await-in-loop
+        const body = (await response.json()) as {error?: string; ok: boolean}
+        if (!body.ok) {
+          throw new Error(body.error ?? 'unknown error')
+        }
+      } else if (webhookUrl) {
+        // eslint-disable-next-line no-await-in-loop
+        await fetchWithRetry(
+          webhookUrl,
+          {body: JSON.stringify(message), headers: {'content-type': 'application/json'}, method: 'POST'},
+          retryOptions,
+        )
+      }
+    } catch (error) {
+      result.status = 'failed'
+      result.error = (error as Error).message
+    }
+
+    results.push(result)
+  }
+
+  return results
+}
+
+/**
+ * Escape the characters Slack treats as control sequences in mrkdwn.
+ */
+function escapeMrkdwn(text: string): string {
+  return text.replaceAll('&', '&amp;').replaceAll('<', '&lt;').replaceAll('>', '&gt;')
+}
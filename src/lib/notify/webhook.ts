@@ -0,0 +1,97 @@
+import {createHmac, randomUUID} from 'node:crypto'
+
+import type {WebhookNotifierConfig} from '../configuration/config.js'
+import type {ReportOutput} from '../reports/index.js'
+import type {DeliveryResult, NotifySource} from './index.js'
+
+import {fetchWithRetry} from './http.js'
+
+/**
+ * The JSON body posted to a webhook.
+ */
+export interface WebhookPayload {
+  pullRequest?: {number: number; url: string}
+  refs: {base: string; head: string}
+  reports: Array<{
+    concern?: string
+    content: string
+    file: string
+    message: string
+    severity?: string
+    signal?: string
+  }>
+  repository?: string
+}
+
+/**
+ * Build the payload for one webhook target from the reports addressed to it.
+ */
+export function createWebhookPayload(reports: ReportOutput[], source: NotifySource): WebhookPayload {
+  return {
+    ...(source.pullRequest ? {pullRequest: source.pullRequest} : {}),
+    refs: source.refs,
+    reports: reports.map((report) => ({
+      concern: report.metadata?.concernId,
+      content: report.content,
+      file: report.metadata?.fileName ?? '',
+      message: report.metadata?.message ?? report.content,
+      severity: report.metadata?.severity,
+      signal: report.metadata?.signalId,
+    })),
+    ...(source.repository ? {repository: source.repository} : {}),
+  }
+}
+
+/**
+ * Sign a body with HMAC-SHA256, as sent in the `X-Distill-Signature-256` header.
+ */
+export function signWebhookBody(body: string, secret: string): string {
+  return `sha256=${createHmac('sha256', secret).update(body).digest('hex')}`
+}
+
+/**
+ * POST the reports addressed to each webhook URL.
+ * Each delivery has a unique `X-Distill-Delivery` id that stays the same across retries, and
+ * bodies are signed in `X-Distill-Signature-256` with the secret in `DISTILL_WEBHOOK_SECRET` when it is set.
+ */
+export async function sendWebhookNotifications(
+  targets: Map<string, ReportOutput[]>,
+  settings: WebhookNotifierConfig,
+  source: NotifySource,
+  secret = process.env.DISTILL_WEBHOOK_SECRET,
+): Promise<DeliveryResult[]> {
+  const retryOptions = {
+    backoff: settings.backoff ?? 1000,
+    retries: settings.retries ?? 3,
+    timeout: settings.timeout ?? 10_000,
+  }
+  const results: DeliveryResult[] = []
+
+  for (const [target, reports] of targets) {
+    const body = JSON.stringify(createWebhookPayload(reports, source))
+    const result: DeliveryResult = {channel: 'webhook', reports: reports.length, status: 'delivered', target}
+    try {
+      // eslint-disable-next-line no-await-in-loop
+      await fetchWithRetry(
+        target,
+        {
+          body,
+          headers: {
+            'content-type': 'application/json',
+            'x-distill-delivery': randomUUID(),
+            ...(secret ? {'x-distill-signature-256': signWebhookBody(body, secret)} : {}),
+          },
+          method: 'POST',
+        },
+        retryOptions,
+      )
+    } catch (error) {
+      result.status = 'failed'
+      result.error = (error as Error).message
+    }
+
+    results.push(result)
+  }
+
+  return results
+}
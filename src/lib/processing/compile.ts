@@ -0,0 +1,138 @@
+import {braceExpand} from 'minimatch'
+import {extname} from 'node:path'
+
+import type {DistillConfig, NotifyConfig, ReportRef, SignalRef, WatchConfig, WatchRef} from '../configuration/config.js'
+import type {ConfigIssue, ConfigPath} from '../configuration/validator.js'
+
+import {isUseReference, resolveSignal} from '../configuration/resolver.js'
+import {ReportValidationError, validateNotifyTargets, validateReport} from '../reports/index.js'
+import {isSupportedExtension} from '../tree-sitter.js'
+import {validateScope, validateWatch} from '../watches/index.js'
+
+/**
+ * Create an issue located at a path in the configuration document.
+ */
+export type IssueFactory = (path: ConfigPath, message: string) => ConfigIssue
+
+/**
+ * Compile every watch query, report template and notify target template in a structurally valid configuration,
+ * so that rules which would silently never fire are caught ahead of time. Scoped watches are checked to
+ * locate their matches, as far as that can be told without a file.
+ *
+ * Defined watches and reports are compiled once where they are defined;
+ * signals that reference them are not compiled again.
+ */
+export async function compileRules(config: DistillConfig, issueAt: IssueFactory): Promise<ConfigIssue[]> {
+  const issues: ConfigIssue[] = []
+
+  const compileWatch = async (watchRef: WatchRef, path: ConfigPath) => {
+    if (isUseReference(watchRef)) return
+    for (const message of await compileWatchConfig(watchRef)) {
+      issues.push(issueAt(path, message))
+    }
+
+    if (watchRef.scope && watchRef.scope !== 'file') {
+      try {
+        await validateScope(watchRef)
+      } catch (error) {
+        const message = `Cannot scope this ${watchRef.type} watch: ${(error as Error).message}`
+        issues.push(issueAt([...path, 'scope'], message))
+      }
+    }
+  }
+
+  const compileReport = (reportRef: ReportRef, path: ConfigPath) => {
+    if (isUseReference(reportRef)) return
+    try {
+      validateReport(reportRef)
+    } catch (error) {
+      const reportPath = error instanceof ReportValidationError ? error.path : []
+      issues.push(issueAt([...path, ...reportPath], (error as Error).message))
+    }
+  }
+
+  const compileNotify = (notify: NotifyConfig, path: ConfigPath) => {
+    try {
+      validateNotifyTargets(notify)
+    } catch (error) {
+      const notifyPath = error instanceof ReportValidationError ? error.path : []
+      issues.push(issueAt([...path, ...notifyPath], (error as Error).message))
+    }
+  }
+
+  const {defined} = config
+
+  for (const [name, watch] of Object.entries(defined?.watches ?? {})) {
+    // eslint-disable-next-line no-await-in-loop
+    await compileWatch(watch, ['defined', 'watches', name])
+  }
+
+  for (const [name, report] of Object.entries(defined?.reports ?? {})) {
+    compileReport(report, ['defined', 'reports', name])
+  }
+
+  const signals: Array<{path: ConfigPath; signalRef: SignalRef}> = [
+    ...Object.entries(defined?.signals ?? {}).map(([name, signal]) => ({
+      path: ['defined', 'signals', name],
+      signalRef: signal,
+    })),
+    ...Object.entries(config.concerns).flatMap(([concernId, concern]) =>
+      concern.signals.map((signalRef, index) => ({path: ['concerns', concernId, 'signals', index], signalRef})),
+    ),
+  ]
+
+  for (const {path, signalRef} of signals) {
+    // Referenced signals are compiled where they are defined
+    if (isUseReference(signalRef)) continue
+    const signal = resolveSignal(signalRef, defined)
+    // eslint-disable-next-line no-await-in-loop
+    await compileWatch(signal.watch, [...path, 'watch'])
+    compileReport(signal.report, [...path, 'report'])
+    if (signal.notify) {
+      compileNotify(signal.notify, [...path, 'notify'])
+    }
+  }
+
+  return issues
+}
+
+/**
+ * Compile a single watch, returning a message for each problem found.
+ * Language-dependent watches are compiled once per language their include patterns can match.
+ */
+async function compileWatchConfig(watch: WatchConfig): Promise<string[]> {
+  const samplePaths = watch.type === 'tsq' && !watch.language ? sampleIncludePaths(watch.include) : [undefined]
+
+  if (samplePaths.length === 0) {
+    return ['Cannot determine the tree-sitter language from the include patterns; set "language" to compile this query']
+  }
+
+  const messages: string[] = []
+  for (const samplePath of samplePaths) {
+    try {
+      // eslint-disable-next-line no-await-in-loop
+      await validateWatch(watch, samplePath)
+    } catch (error) {
+      const suffix = samplePath ? ` (as ${extname(samplePath)})` : ''
+      messages.push(`Invalid ${watch.type} watch${suffix}: ${(error as Error).message.trim()}`)
+    }
+  }
+
+  return messages
+}
+
+/**
+ * Derive one sample file path per supported extension matched by the include patterns,
+ * e.g. `src/**\/*.{ts,tsx}` yields `sample.ts` and `sample.tsx`.
+ */
+function sampleIncludePaths(include: string | string[]): string[] {
+  const patterns = Array.isArray(include) ? include : [include]
+  const extensions = new Set(
+    patterns
+      .filter((pattern) => !pattern.startsWith('!'))
+      .flatMap((pattern) => braceExpand(pattern))
+      .map((pattern) => extname(pattern).toLowerCase())
+      .filter((ext) => isSupportedExtension(ext)),
+  )
+  return [...extensions].map((ext) => `sample${ext}`)
+}
@@ -0,0 +1,36 @@
+import {minimatch} from 'minimatch'
+
+/**
+ * Include and exclude glob patterns for selecting files.
+ */
+export interface FilePatterns {
+  /** Patterns for files to skip */
+  exclude?: string | string[]
+  /** Patterns for files to select; `!`-prefixed entries act like `exclude` */
+  include?: string | string[]
+}
+
+/**
+ * Check whether a file path is selected by include/exclude patterns.
+ *
+ * A path is selected when it matches at least one positive `include` pattern (or there are
+ * none) and matches no `exclude` pattern and no `!`-negated `include` pattern.
+ * The order of patterns does not matter.
+ */
+export function matchesPatterns(filePath: string, patterns: FilePatterns): boolean {
+  const include = toArray(patterns.include)
+  const positive = include.filter((pattern) => !pattern.startsWith('!'))
+  const negated = include.filter((pattern) => pattern.startsWith('!')).map((pattern) => pattern.slice(1))
+  const negative = [...negated, ...toArray(patterns.exclude)]
+
+  if (positive.length > 0 && !positive.some((pattern) => minimatch(filePath, pattern))) {
+    return false
+  }
+
+  return !negative.some((pattern) => minimatch(filePath, pattern))
+}
+
+function toArray(patterns?: string | string[]): string[] {
+  if (patterns === undefined) return []
+  return Array.isArray(patterns) ? patterns : [patterns]
+}
@@ -1,20 +1,46 @@
-import {minimatch} from 'minimatch'
-
-import type {DefinedBlock, DistillConfig, NotifyConfig, Signal} from '../configuration/config.js'
+import type {
+  DefinedBlock,
+  DistillConfig,
+  NotifyConfig,
+  Severity,
+  Signal,
+  SignalRef,
+  Stakeholder,
+} from '../configuration/config.js'
 import type {File, FileVersions} from '../diff/parser.js'
-import type {ReportOutput} from '../reports/index.js'
+import type {ReportContext, ReportOutput} from '../reports/index.js'
 import type {ProcessingContext} from './types.js'
 
-import {resolveReport, resolveSignal, resolveWatch} from '../configuration/resolver.js'
-import {executeReport} from '../reports/index.js'
-import {applyWatch} from '../watches/index.js'
+import {isUseReference, resolveReport, resolveSignal, resolveWatch} from '../configuration/resolver.js'
+import {executeReport, renderNotifyTargets} from '../reports/index.js'
+import {applyWatch, type FilterResult} from '../watches/index.js'
+import {matchesPatterns} from './patterns.js'
+import {applyScope} from './scope.js'
 
 /** Result of processing files through all concerns */
 export interface ProcessingResult {
+  /** Signals that failed on a file; the remaining signals and files are still processed */
+  errors: SignalError[]
   /** All reports generated */
   reports: ReportOutput[]
 }
 
+/**
+ * A signal that failed on a file, e.g. because the file could not be parsed by its watch.
+ */
+export class SignalError extends Error {
+  constructor(
+    message: string,
+    public readonly concernId: string,
+    public readonly signalId: string,
+    public readonly filePath: string,
+    options?: ErrorOptions,
+  ) {
+    super(message, options)
+    this.name = 'SignalError'
+  }
+}
+
 /**
  * Process files through all concerns and their signals.
  */
@@ -23,25 +49,47 @@ export async function processFiles(
   config: DistillConfig,
   context: ProcessingContext,
 ): Promise<ProcessingResult> {
+  const errors: SignalError[] = []
   const reports: ReportOutput[] = []
 
   for (const file of files) {
+    const filePath = file.newPath || file.oldPath
+
+    // Skip files ignored globally
+    if (config.ignore && !matchesPatterns(filePath, {exclude: config.ignore})) {
+      continue
+    }
+
     for (const [concernId, concern] of Object.entries(config.concerns)) {
-      for (const signalRef of concern.signals) {
-        // eslint-disable-next-line no-await-in-loop
-        const signalReports = await processSignal({
-          concernId,
-          context,
-          defined: config.defined,
-          file,
-          signalRef,
-        })
-        reports.push(...signalReports)
+      // Skip concerns whose include/exclude filters rule out this file
+      if (!matchesPatterns(filePath, concern)) {
+        continue
+      }
+
+      for (const [signalIndex, signalRef] of concern.signals.entries()) {
+        try {
+          // eslint-disable-next-line no-await-in-loop
+          const signalReports = await processSignal({
+            concernId,
+            context,
+            defined: config.defined,
+            file,
+            signalIndex,
+            severity: concern.severity,
+            signalRef,
+            stakeholders: concern.stakeholders,
+          })
+          reports.push(...signalReports)
+        } catch (error) {
+          // A file the signal cannot handle should not hide what the other signals found
+          if (!(error instanceof SignalError)) throw error
+          errors.push(error)
+        }
       }
     }
   }
 
-  return {reports}
+  return {errors, reports}
 }
 
 /** Options for processing a signal against a file */
@@ -50,15 +98,20 @@ interface ProcessSignalOptions {
   context: ProcessingContext
   defined?: DefinedBlock
   file: File
-  signalRef: Signal | {use: string}
+  /** Default severity of the concern's signals */
+  severity?: Severity
+  signalIndex: number
+  signalRef: SignalRef
+  stakeholders?: Stakeholder[]
 }
 
 /**
  * Process a single signal against a file.
  * Returns reports if the signal matches and produces output.
+ * @throws SignalError when the watch or report fails on the file
  */
 async function processSignal(options: ProcessSignalOptions): Promise<ReportOutput[]> {
-  const {context, defined, file, signalRef} = options
+  const {concernId, context, defined, file, severity, signalIndex, signalRef, stakeholders} = options
   const filePath = file.newPath || file.oldPath
 
   // Resolve the signal reference
@@ -67,16 +120,33 @@ async function processSignal(options: ProcessSignalOptions): Promise<ReportOutpu
   // Resolve the watch reference
   const watch = resolveWatch(signal.watch, defined)
 
-  // Check if file matches the watch's include pattern(s)
-  if (!matchesInclude(filePath, watch.include)) {
+  // Check if file matches the watch's include/exclude pattern(s)
+  if (!matchesPatterns(filePath, watch)) {
     return []
   }
 
   // Get file versions for comparison
   const versions = await getFileVersions(file, context)
 
-  // Apply the watch extraction
-  const watchResult = await applyWatch(watch, versions, filePath)
+  const signalId = getSignalId(signal, signalRef, signalIndex)
+  const signalName = `Signal "${signalId}" of concern "${concernId}"`
+
+  // Apply the watch extraction, narrowed to the changed lines when the watch is scoped
+  let watchResult: FilterResult | null
+  try {
+    watchResult = await applyWatch(watch, versions, filePath)
+    if (watchResult && watch.scope) {
+      watchResult = await applyScope(watchResult, file, watch.scope)
+    }
+  } catch (error) {
+    throw new SignalError(
+      `${signalName} failed on ${filePath} (${watch.type} watch): ${(error as Error).message}`,
+      concernId,
+      signalId,
+      filePath,
+      {cause: error},
+    )
+  }
 
   if (!watchResult) {
     return []
@@ -84,22 +154,44 @@ async function processSignal(options: ProcessSignalOptions): Promise<ReportOutpu
 
   // Execute the report
   const report = resolveReport(signal.report, defined)
-  const reportOutput = executeReport(report, watchResult, {filePath})
+  const reportContext: ReportContext = {
+    concernId,
+    filePath,
+    ...(file.oldPath && file.oldPath !== filePath ? {oldFilePath: file.oldPath} : {}),
+    severity: signal.severity ?? severity ?? 'info',
+    signalId,
+    stakeholders,
+    watchType: watch.type,
+  }
+  let reportOutput: ReportOutput
+  try {
+    reportOutput = executeReport(report, watchResult, reportContext)
+  } catch (error) {
+    throw new SignalError(
+      `${signalName} failed on ${filePath} (${report.type} report): ${(error as Error).message}`,
+      concernId,
+      signalId,
+      filePath,
+      {cause: error},
+    )
+  }
 
-  // Attach notify config to report metadata for downstream processing
+  // Attach notify targets, rendered like the report, for downstream processing
   if (signal.notify) {
-    reportOutput.notify = signal.notify
+    reportOutput.notify = renderNotifyTargets(signal.notify, watchResult, reportContext)
   }
 
   return [reportOutput]
 }
 
 /**
- * Check if a file path matches an include pattern (string or array of strings).
+ * Derive a stable identifier for a signal: its explicit id, the defined signal it references,
+ * or its position in the concern.
  */
-function matchesInclude(filePath: string, include: string | string[]): boolean {
-  const patterns = Array.isArray(include) ? include : [include]
-  return patterns.some((pattern) => minimatch(filePath, pattern))
+export function getSignalId(signal: Signal, signalRef: SignalRef, signalIndex: number): string {
+  if (signal.id) return signal.id
+  if (isUseReference(signalRef)) return signalRef.use
+  return String(signalIndex)
 }
 
 /**
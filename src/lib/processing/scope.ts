@@ -0,0 +1,93 @@
+import type {SourceMatch, WatchScope} from '../configuration/config.js'
+import type {File} from '../diff/parser.js'
+import type {FilterResult} from '../watches/index.js'
+
+import {createFilterResult} from '../watches/utils.js'
+
+/**
+ * Line numbers touched by a diff, in the old and new version of a file.
+ */
+export interface ChangedLines {
+  new: Set<number>
+  old: Set<number>
+}
+
+/**
+ * Collect the lines a diff touches, either only the inserted/deleted lines or every line of each hunk.
+ */
+export function getChangedLines(file: File, scope: Exclude<WatchScope, 'file'>): ChangedLines {
+  const lines: ChangedLines = {new: new Set(), old: new Set()}
+
+  for (const hunk of file.hunks) {
+    if (scope === 'changed-hunks') {
+      for (let line = hunk.oldStart; line < hunk.oldStart + hunk.oldLines; line++) lines.old.add(line)
+      for (let line = hunk.newStart; line < hunk.newStart + hunk.newLines; line++) lines.new.add(line)
+      continue
+    }
+
+    for (const change of hunk.changes) {
+      if (change.type === 'delete') lines.old.add(change.lineNumber)
+      if (change.type === 'insert') lines.new.add(change.lineNumber)
+    }
+  }
+
+  return lines
+}
+
+/**
+ * Narrow a watch result to the matches that overlap the changed lines of the diff.
+ * The artifacts and diff are rebuilt from those matches, so the signal only fires when they differ.
+ *
+ * @throws Error if the watch did not locate its matches, e.g. a jq query that computes its output
+ */
+export async function applyScope(result: FilterResult, file: File, scope: WatchScope): Promise<FilterResult | null> {
+  if (scope === 'file') {
+    return result
+  }
+
+  if (!result.left.matches && !result.right.matches) {
+    throw new Error(`scope "${scope}" needs located matches, but the query computes values instead of selecting them`)
+  }
+
+  const lines = getChangedLines(file, scope)
+  const left = (result.left.matches ?? []).filter((match) => overlaps(match, lines.old))
+  const right = (result.right.matches ?? []).filter((match) => overlaps(match, lines.new))
+
+  const scoped = await createFilterResult(joinMatches(left), joinMatches(right))
+  if (!scoped) {
+    return null
+  }
+
+  return {
+    ...scoped,
+    ...(result.context ? {context: result.context} : {}),
+    left: {...scoped.left, ...scopeCaptures(result.left.captures, left), matches: left},
+    right: {...scoped.right, ...scopeCaptures(result.right.captures, right), matches: right},
+  }
+}
+
+/**
+ * Keep only the captures of the matches in scope, each once even when it belongs to several matches.
+ */
+function scopeCaptures(captures: Record<string, string>[] | undefined, matches: SourceMatch[]) {
+  if (!captures) return {}
+  return {captures: [...new Set(matches.flatMap((match) => (match.captures ? [match.captures] : [])))]}
+}
+
+function overlaps(match: SourceMatch, lines: Set<number>): boolean {
+  const {endColumn, endLine, startLine} = match.range
+  // A match ending right after a newline doesn't cover the line that follows it
+  const lastLine = endColumn === 1 && endLine > startLine ? endLine - 1 : endLine
+
+  for (let line = startLine; line <= lastLine; line++) {
+    if (lines.has(line)) return true
+  }
+
+  return false
+}
+
+function joinMatches(matches: SourceMatch[]): string {
+  // Separate multi-line nodes with a blank line, like the tsq and ast-grep artifacts
+  const separator = matches.some((match) => match.text.includes('\n')) ? '\n\n' : '\n'
+  return matches.map((match) => match.text).join(separator)
+}
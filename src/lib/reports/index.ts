@@ -1,17 +1,76 @@
+import {Ajv} from 'ajv'
 import Handlebars from 'handlebars'
+import {createHash} from 'node:crypto'
 
-import type {FilterResult, HandlebarsReport, ReportConfig} from '../configuration/config.js'
+import type {
+  FilterResult,
+  HandlebarsReport,
+  JsonReport,
+  NotifyConfig,
+  ReportConfig,
+  SarifReport,
+  Severity,
+  SourceMatch,
+  SourceRange,
+  Stakeholder,
+  WatchType,
+} from '../configuration/config.js'
+
+import {parseSelector, select} from './selector.js'
 
 // Re-export report types for convenience
-export type {HandlebarsReport, ReportConfig} from '../configuration/config.js'
+export type {HandlebarsReport, JsonReport, ReportConfig, SarifReport} from '../configuration/config.js'
+
+const ajv = new Ajv({allErrors: true})
+
+/**
+ * Position of a finding in the old or new version of a file.
+ */
+export interface ReportLocation extends SourceRange {
+  path: string
+}
 
 export interface ReportMetadata {
+  concernId?: string
   // eslint-disable-next-line @typescript-eslint/no-explicit-any
   context?: Record<string, any>[]
+  /** Object produced by a json report */
+  data?: Record<string, unknown>
   diffText: string
   fileName: string
+  /** Fingerprint of the finding, set in `--json` output; see `getReportFingerprint` */
+  id?: string
+  /** Line range within the filtered artifact, not the source file; see `locations` */
   lineRange?: {end: number; start: number}
+  /** Where the extracted matches that changed are in the old and new file */
+  locations?: {new: ReportLocation[]; old: ReportLocation[]}
+  /** Markdown variant of `message`, rendered by sarif reports that define one */
+  markdown?: string
   message: string
+  /** How serious the finding is; set for every report produced by a signal */
+  severity?: Severity
+  signalId?: string
+  stakeholders?: Stakeholder[]
+  watchType?: WatchType
+}
+
+/**
+ * Information about where a report came from, available to report templates.
+ */
+export interface ReportContext {
+  /** Concern the signal belongs to */
+  concernId?: string
+  filePath: string
+  /** Path of the file before the change, when it differs from `filePath` (renames) */
+  oldFilePath?: string
+  /** Severity of the signal, resolved from the signal or its concern */
+  severity?: Severity
+  /** Explicit signal id, `#defined/signals/<name>` reference, or index within the concern */
+  signalId?: string
+  /** Stakeholders of the concern the signal belongs to */
+  stakeholders?: Stakeholder[]
+  /** Type of the resolved watch that produced the result */
+  watchType?: WatchType
 }
 
 export interface ReportOutput {
@@ -19,6 +78,37 @@ export interface ReportOutput {
   metadata?: ReportMetadata
 }
 
+/**
+ * Thrown by `validateReport`, with the path of the offending property within the report.
+ */
+export class ReportValidationError extends Error {
+  constructor(
+    message: string,
+    public readonly path: string[],
+  ) {
+    super(message)
+    this.name = 'ReportValidationError'
+  }
+}
+
+/**
+ * Values available to report templates and selectors.
+ */
+interface ReportData {
+  concernId?: string
+  diffText: string
+  filePath: string
+  left: {artifact: string; captures: Record<string, string>[]}
+  locations: {new: ReportLocation[]; old: ReportLocation[]}
+  /** `filePath` split on `/`, e.g. `{{pathSegments.[1]}}` is `api` for `services/api/main.ts` */
+  pathSegments: string[]
+  right: {artifact: string; captures: Record<string, string>[]}
+  severity?: Severity
+  signalId?: string
+  stakeholders: Stakeholder[]
+  watchType?: WatchType
+}
+
 /**
  * Execute a report by rendering the template with the watch result.
  * Diff text and artifacts are marked as safe strings to avoid HTML escaping.
@@ -26,11 +116,124 @@ export interface ReportOutput {
 export function executeReport(
   report: ReportConfig,
   filterResult: FilterResult,
-  context: {filePath: string},
+  context: ReportContext,
 ): ReportOutput {
-  // Currently only handlebars is supported
-  // Future: switch on report.type for different report formats
-  return executeHandlebarsReport(report, filterResult, context)
+  switch (report.type) {
+    case 'handlebars': {
+      return executeHandlebarsReport(report, filterResult, context)
+    }
+
+    case 'json': {
+      return executeJsonReport(report, filterResult, context)
+    }
+
+    case 'sarif': {
+      return executeSarifReport(report, filterResult, context)
+    }
+
+    default: {
+      const exhaustiveCheck: never = report
+      throw new Error(`Unsupported report type: ${(exhaustiveCheck as ReportConfig).type}`)
+    }
+  }
+}
+
+/**
+ * Check a report configuration without executing it, e.g. by compiling its templates.
+ *
+ * @throws ReportValidationError describing why the report cannot be rendered
+ */
+export function validateReport(report: ReportConfig): void {
+  switch (report.type) {
+    case 'handlebars': {
+      precompileTemplate(report.template, ['template'])
+      break
+    }
+
+    case 'json': {
+      for (const [name, field] of Object.entries(report.fields)) {
+        if (typeof field === 'string') {
+          precompileTemplate(field, ['fields', name])
+          continue
+        }
+
+        try {
+          parseSelector(field.select)
+        } catch (error) {
+          throw new ReportValidationError((error as Error).message, ['fields', name, 'select'])
+        }
+      }
+
+      if (report.schema) {
+        try {
+          ajv.compile(report.schema)
+        } catch (error) {
+          throw new ReportValidationError(`Invalid JSON Schema: ${(error as Error).message}`, ['schema'])
+        }
+      }
+
+      break
+    }
+
+    case 'sarif': {
+      precompileTemplate(report.template, ['template'])
+      if (report.markdown !== undefined) {
+        precompileTemplate(report.markdown, ['markdown'])
+      }
+
+      break
+    }
+
+    default: {
+      const exhaustiveCheck: never = report
+      throw new Error(`Unsupported report type: ${(exhaustiveCheck as ReportConfig).type}`)
+    }
+  }
+}
+
+/**
+ * Render the Handlebars in a signal's notify targets with the same data as its report,
+ * so one signal can notify e.g. `#team-{{pathSegments.[1]}}` depending on the file.
+ * Targets that render empty are dropped.
+ */
+export function renderNotifyTargets(
+  notify: NotifyConfig,
+  filterResult: FilterResult,
+  context: ReportContext,
+): NotifyConfig {
+  const data = createReportData(filterResult, context)
+  const rendered: NotifyConfig = {}
+  for (const [channel, target] of Object.entries(notify) as Array<[keyof NotifyConfig, string | undefined]>) {
+    // Targets are addresses rather than HTML, so templates don't escape anything
+    const value = target?.includes('{{') ? Handlebars.compile(target, {noEscape: true})(data).trim() : target
+    if (value) {
+      rendered[channel] = value
+    }
+  }
+
+  return rendered
+}
+
+/**
+ * Check the templates in a signal's notify targets without rendering them.
+ *
+ * @throws ReportValidationError with the channel of the offending target
+ */
+export function validateNotifyTargets(notify: NotifyConfig): void {
+  for (const [channel, target] of Object.entries(notify)) {
+    if (typeof target === 'string') {
+      precompileTemplate(target, [channel])
+    }
+  }
+}
+
+function precompileTemplate(source: string, path: string[]): void {
+  try {
+    // Handlebars.compile is lazy, so precompile to surface template syntax errors now
+    Handlebars.precompile(source)
+  } catch (error) {
+    throw new ReportValidationError(`Invalid template: ${(error as Error).message}`, path)
+  }
 }
 
 /**
@@ -39,28 +242,158 @@ export function executeReport(
 function executeHandlebarsReport(
   report: HandlebarsReport,
   filterResult: FilterResult,
-  context: {filePath: string},
+  context: ReportContext,
 ): ReportOutput {
-  const template = Handlebars.compile(report.template)
-  // Mark diff text and artifacts as safe to prevent HTML escaping
-  const content = template({
-    diffText: new Handlebars.SafeString(filterResult.diffText),
+  const data = createReportData(filterResult, context)
+  const content = renderTemplate(report.template, data)
+
+  return {
+    content,
+    metadata: createMetadata(content, filterResult, data),
+  }
+}
+
+/**
+ * Execute a json report by evaluating each field, and check the result against the report's schema.
+ * The content is the object as a single line of JSON.
+ *
+ * @throws Error if the object does not match the schema
+ */
+function executeJsonReport(report: JsonReport, filterResult: FilterResult, context: ReportContext): ReportOutput {
+  const data = createReportData(filterResult, context)
+
+  // Output is JSON rather than HTML, so templates don't escape anything
+  const render = (source: string) => Handlebars.compile(source, {noEscape: true})(data)
+
+  const output: Record<string, unknown> = {}
+  for (const [name, field] of Object.entries(report.fields)) {
+    const value = typeof field === 'string' ? render(field) : select(data, field.select)
+    if (value !== undefined) {
+      output[name] = value
+    }
+  }
+
+  if (report.schema) {
+    const validate = ajv.compile(report.schema)
+    if (!validate(output)) {
+      const errors = ajv.errorsText(validate.errors, {dataVar: 'output'})
+      throw new Error(`Report output does not match its schema: ${errors}`)
+    }
+  }
+
+  const content = JSON.stringify(output)
+  return {
+    content,
+    metadata: {...createMetadata(content, filterResult, data), data: output},
+  }
+}
+
+/**
+ * Execute a sarif report by rendering the result message, and its Markdown variant when configured.
+ * The SARIF log itself is assembled from all reports by `createSarifLog`.
+ */
+function executeSarifReport(report: SarifReport, filterResult: FilterResult, context: ReportContext): ReportOutput {
+  const data = createReportData(filterResult, context)
+  const content = renderTemplate(report.template, data)
+  const metadata = createMetadata(content, filterResult, data)
+  if (report.markdown !== undefined) {
+    metadata.markdown = renderTemplate(report.markdown, data)
+  }
+
+  return {content, metadata}
+}
+
+function createReportData(filterResult: FilterResult, context: ReportContext): ReportData {
+  return {
+    concernId: context.concernId,
+    diffText: filterResult.diffText,
     filePath: context.filePath,
-    left: {artifact: new Handlebars.SafeString(filterResult.left.artifact)},
-    right: {artifact: new Handlebars.SafeString(filterResult.right.artifact)},
+    left: {artifact: filterResult.left.artifact, captures: filterResult.left.captures ?? []},
+    locations: getChangedLocations(filterResult, context),
+    pathSegments: context.filePath.split('/'),
+    right: {artifact: filterResult.right.artifact, captures: filterResult.right.captures ?? []},
+    severity: context.severity,
+    signalId: context.signalId,
+    stakeholders: context.stakeholders ?? [],
+    watchType: context.watchType,
+  }
+}
+
+/**
+ * Render a report template with the watch result.
+ */
+function renderTemplate(source: string, data: ReportData): string {
+  const template = Handlebars.compile(source)
+  // Mark diff text and artifacts as safe to prevent HTML escaping
+  return template({
+    ...data,
+    diffText: new Handlebars.SafeString(data.diffText),
+    left: {...data.left, artifact: new Handlebars.SafeString(data.left.artifact)},
+    right: {...data.right, artifact: new Handlebars.SafeString(data.right.artifact)},
   })
+}
 
-  // Basic metadata population.
-  const metadata: ReportMetadata = {
+/**
+ * Describe a rendered report for JSON and SARIF output.
+ */
+function createMetadata(content: string, filterResult: FilterResult, data: ReportData): ReportMetadata {
+  const {locations} = data
+  return {
     diffText: filterResult.diffText,
-    fileName: context.filePath,
+    fileName: data.filePath,
     message: content, // Use the rendered content as the default message
+    ...(data.concernId ? {concernId: data.concernId} : {}),
+    ...(data.signalId ? {signalId: data.signalId} : {}),
+    ...(data.severity ? {severity: data.severity} : {}),
+    ...(data.watchType ? {watchType: data.watchType} : {}),
     ...(filterResult.lineRange ? {lineRange: filterResult.lineRange} : {}),
+    ...(locations.new.length > 0 || locations.old.length > 0 ? {locations} : {}),
     ...(filterResult.context ? {context: filterResult.context} : {}),
+    ...(data.stakeholders.length > 0 ? {stakeholders: data.stakeholders} : {}),
   }
+}
+
+/**
+ * Fingerprint a finding so it can be recognized across runs even as surrounding lines move.
+ * Findings of the same signal in the same file with the same message share a fingerprint.
+ */
+export function getReportFingerprint(metadata: ReportMetadata): string {
+  return createHash('sha256')
+    .update([metadata.concernId ?? '', metadata.signalId ?? '', metadata.fileName, metadata.message].join('\0'))
+    .digest('hex')
+    .slice(0, 32)
+}
+
+/**
+ * Locate the matches that changed: those on one side with no identical match on the other side.
+ * Unchanged matches are left out, so locations point at what the finding is about.
+ */
+export function getChangedLocations(
+  filterResult: FilterResult,
+  context: ReportContext,
+): {new: ReportLocation[]; old: ReportLocation[]} {
+  const oldPath = context.oldFilePath ?? context.filePath
+  const toLocation = (path: string) => (match: SourceMatch) => ({path, ...match.range})
 
   return {
-    content,
-    metadata,
+    new: unmatched(filterResult.right.matches, filterResult.left.matches).map(toLocation(context.filePath)),
+    old: unmatched(filterResult.left.matches, filterResult.right.matches).map(toLocation(oldPath)),
   }
 }
+
+/**
+ * Matches whose text does not appear on the other side, counting duplicates.
+ */
+function unmatched(matches: SourceMatch[] = [], others: SourceMatch[] = []): SourceMatch[] {
+  const remaining = new Map<string, number>()
+  for (const other of others) {
+    remaining.set(other.text, (remaining.get(other.text) ?? 0) + 1)
+  }
+
+  return matches.filter((match) => {
+    const count = remaining.get(match.text) ?? 0
+    if (count === 0) return true
+    remaining.set(match.text, count - 1)
+    return false
+  })
+}
@@ -0,0 +1,182 @@
+import type {Severity} from '../configuration/config.js'
+import type {ReportLocation, ReportMetadata, ReportOutput} from './index.js'
+
+import {getReportFingerprint} from './index.js'
+
+export const SARIF_SCHEMA = 'https://json.schemastore.org/sarif-2.1.0.json'
+export const SARIF_VERSION = '2.1.0'
+
+/** SARIF result level for each severity */
+const SARIF_LEVELS: Record<Severity, SarifResult['level']> = {
+  error: 'error',
+  info: 'note',
+  warning: 'warning',
+}
+
+/**
+ * The subset of a SARIF 2.1.0 log that distill produces.
+ * See https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+ */
+export interface SarifLog {
+  $schema: string
+  runs: SarifRun[]
+  version: typeof SARIF_VERSION
+}
+
+export interface SarifRun {
+  results: SarifResult[]
+  tool: {driver: SarifDriver}
+}
+
+export interface SarifDriver {
+  informationUri?: string
+  name: string
+  rules: SarifRule[]
+  version?: string
+}
+
+/**
+ * A concern, as a SARIF reporting descriptor.
+ */
+export interface SarifRule {
+  id: string
+  properties?: {stakeholders: string[]}
+  shortDescription: {text: string}
+}
+
+/**
+ * A fired signal, as a SARIF result.
+ */
+export interface SarifResult {
+  level: 'error' | 'note' | 'warning'
+  locations: SarifLocation[]
+  message: {markdown?: string; text: string}
+  partialFingerprints: Record<string, string>
+  properties?: {signalId?: string; watchType?: string}
+  relatedLocations?: SarifLocation[]
+  ruleId: string
+  ruleIndex: number
+}
+
+export interface SarifLocation {
+  message?: {text: string}
+  physicalLocation: {
+    artifactLocation: {uri: string}
+    region?: SarifRegion
+  }
+}
+
+/**
+ * A text region; like `SourceRange`, lines and columns are 1-based and `endColumn` is exclusive.
+ */
+export interface SarifRegion {
+  endColumn: number
+  endLine: number
+  startColumn: number
+  startLine: number
+}
+
+/**
+ * Identifies the tool in the SARIF log.
+ */
+export interface SarifTool {
+  informationUri?: string
+  name: string
+  version?: string
+}
+
+/** Rule id for reports that do not belong to a concern */
+const DEFAULT_RULE_ID = 'distill'
+
+/**
+ * Build a SARIF log from reports.
+ * Concerns become rules, each report becomes a result, and the changed matches in the
+ * new version of the file become its locations. Results without source locations point
+ * at the whole file. Locations in the old version are listed as related locations.
+ */
+export function createSarifLog(reports: ReportOutput[], tool: SarifTool): SarifLog {
+  const rules: SarifRule[] = []
+  const ruleIndexes = new Map<string, number>()
+
+  const results = reports.map((report): SarifResult => {
+    const metadata: ReportMetadata = report.metadata ?? {diffText: '', fileName: '', message: report.content}
+    const ruleId = metadata.concernId ?? DEFAULT_RULE_ID
+
+    let ruleIndex = ruleIndexes.get(ruleId)
+    if (ruleIndex === undefined) {
+      ruleIndex = rules.push(createRule(ruleId, metadata)) - 1
+      ruleIndexes.set(ruleId, ruleIndex)
+    }
+
+    const newLocations = metadata.locations?.new ?? []
+    const oldLocations = metadata.locations?.old ?? []
+    const fileLocations: SarifLocation[] = metadata.fileName
+      ? [{physicalLocation: {artifactLocation: {uri: toUri(metadata.fileName)}}}]
+      : []
+
+    return {
+      level: SARIF_LEVELS[metadata.severity ?? 'info'],
+      locations: newLocations.length > 0 ? newLocations.map((location) => toSarifLocation(location)) : fileLocations,
+      message: {
+        text: metadata.message,
+        ...(metadata.markdown === undefined ? {} : {markdown: metadata.markdown}),
+      },
+      partialFingerprints: {'distill/v1': getReportFingerprint(metadata)},
+      ...(metadata.signalId || metadata.watchType
+        ? {properties: {signalId: metadata.signalId, watchType: metadata.watchType}}
+        : {}),
+      ...(oldLocations.length > 0
+        ? {relatedLocations: oldLocations.map((location) => toSarifLocation(location, 'Before the change'))}
+        : {}),
+      ruleId,
+      ruleIndex,
+    }
+  })
+
+  return {
+    $schema: SARIF_SCHEMA,
+    runs: [
+      {
+        results,
+        tool: {
+          driver: {
+            ...(tool.informationUri ? {informationUri: tool.informationUri} : {}),
+            name: tool.name,
+            rules,
+            ...(tool.version ? {version: tool.version} : {}),
+          },
+        },
+      },
+    ],
+    version: SARIF_VERSION,
+  }
+}
+
+function createRule(ruleId: string, metadata: ReportMetadata): SarifRule {
+  const stakeholders = metadata.stakeholders?.map((stakeholder) => stakeholder.name) ?? []
+  return {
+    id: ruleId,
+    ...(stakeholders.length > 0 ? {properties: {stakeholders}} : {}),
+    shortDescription: {
+      text: ruleId === DEFAULT_RULE_ID ? 'Changes found by distill' : `Changes relevant to the ${ruleId} concern`,
+    },
+  }
+}
+
+function toSarifLocation(location: ReportLocation, message?: string): SarifLocation {
+  const {endColumn, endLine, path, startColumn, startLine} = location
+  return {
+    ...(message ? {message: {text: message}} : {}),
+    physicalLocation: {
+      artifactLocation: {uri: toUri(path)},
+      region: {endColumn, endLine, startColumn, startLine},
+    },
+  }
+}
+
+/**
+ * Repository-relative paths are valid relative URI references once special characters are escaped.
+ */
+function toUri(path: string): string {
+  return encodeURI(path.replaceAll('\\', '/'))
+}
@@ -0,0 +1,85 @@
+/**
+ * One step of a selector: a property key, an array index (negative counts from the end), or a wildcard.
+ */
+export type SelectorSegment = {index: number; kind: 'index'} | {key: string; kind: 'key'} | {kind: 'wildcard'}
+
+const SEGMENT = /^(?:\.([A-Za-z_$][\w$-]*)|\.\*|\[\*\]|\[(-?\d+)\]|\[(['"])(.*?)\3\])/
+
+/**
+ * Parse a JSONPath-like selector such as `$.right.captures[*].version`.
+ * The leading `$` and the first dot are optional.
+ *
+ * @throws Error if the selector has a syntax error
+ */
+export function parseSelector(selector: string): SelectorSegment[] {
+  let rest = selector.trim().replace(/^\$/, '')
+  if (rest && !rest.startsWith('.') && !rest.startsWith('[')) {
+    rest = `.${rest}`
+  }
+
+  const segments: SelectorSegment[] = []
+  while (rest) {
+    const match = SEGMENT.exec(rest)
+    if (!match) {
+      throw new Error(`Invalid selector "${selector}" at "${rest}"`)
+    }
+
+    if (match[1] !== undefined) {
+      segments.push({key: match[1], kind: 'key'})
+    } else if (match[2] !== undefined) {
+      segments.push({index: Number(match[2]), kind: 'index'})
+    } else if (match[4] === undefined) {
+      segments.push({kind: 'wildcard'})
+    } else {
+      segments.push({key: match[4], kind: 'key'})
+    }
+
+    rest = rest.slice(match[0].length)
+  }
+
+  return segments
+}
+
+/**
+ * Select a value from data with a JSONPath-like selector.
+ * Selectors with a wildcard return an array of every value found; others return the value,
+ * or `undefined` when the path does not exist.
+ *
+ * @throws Error if the selector has a syntax error
+ */
+export function select(data: unknown, selector: string): unknown {
+  const segments = parseSelector(selector)
+
+  let values: unknown[] = [data]
+  for (const segment of segments) {
+    values = values.flatMap((value) => step(value, segment))
+  }
+
+  if (segments.some((segment) => segment.kind === 'wildcard')) {
+    return values
+  }
+
+  return values[0]
+}
+
+function step(value: unknown, segment: SelectorSegment): unknown[] {
+  if (typeof value !== 'object' || value === null) {
+    return []
+  }
+
+  switch (segment.kind) {
+    case 'index': {
+      if (!Array.isArray(value)) return []
+      const item = value.at(segment.index)
+      return item === undefined ? [] : [item]
+    }
+
+    case 'key': {
+      return Object.hasOwn(value, segment.key) ? [(value as Record<string, unknown>)[segment.key]] : []
+    }
+
+    case 'wildcard': {
+      return Array.isArray(value) ? value : Object.values(value)
+    }
+  }
+}
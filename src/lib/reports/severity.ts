@@ -0,0 +1,11 @@
+import type {Severity} from '../configuration/config.js'
+
+/** Severities from least to most serious */
+export const SEVERITIES: readonly Severity[] = ['info', 'warning', 'error']
+
+/**
+ * Whether a severity is at least as serious as a threshold.
+ */
+export function meetsSeverity(severity: Severity, threshold: Severity): boolean {
+  return SEVERITIES.indexOf(severity) >= SEVERITIES.indexOf(threshold)
+}
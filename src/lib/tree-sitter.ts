@@ -37,6 +37,13 @@ const LANGUAGE_WASM_MAP: Record<string, string> = {
 
 export type SupportedExtension = keyof typeof LANGUAGE_WASM_MAP
 
+/**
+ * Check whether tree-sitter has a grammar for the given file extension.
+ */
+export function isSupportedExtension(ext: string): ext is SupportedExtension {
+  return ext.toLowerCase() in LANGUAGE_WASM_MAP
+}
+
 // Cache loaded languages
 const languageCache = new Map<string, import('web-tree-sitter').Language>()
 
@@ -1,9 +1,10 @@
-import {spawn} from 'node:child_process'
+import type {NapiConfig, SgNode} from '@ast-grep/napi'
 
+import type {AstGrepRule, AstGrepTransformation, SourceMatch} from '../configuration/config.js'
 import type {FileVersions} from '../diff/parser.js'
 import type {FilterApplier, FilterResult} from './types.js'
 
-import {processFilter} from './utils.js'
+import {createRangeLocator, type ExtractedContent, processFilter} from './utils.js'
 
 /**
  * Pattern object for ast-grep with context and selector.
@@ -70,8 +71,26 @@ export interface AstGrepPatternObject {
  *       context: 'class C { static override args = $ARGS }'
  *       selector: public_field_definition
  * ```
+ *
+ * @example
+ * ```yaml
+ * # Find fetch calls that are not inside a try block
+ * filters:
+ *   - type: ast-grep
+ *     language: typescript
+ *     rule:
+ *       pattern: fetch($$$ARGS)
+ *       not:
+ *         inside:
+ *           kind: try_statement
+ *           stopBy: end
+ * ```
  */
 export type AstGrepFilterConfig = {
+  /**
+   * Extra rules that captured metavariables must satisfy, keyed by metavariable name without `$`.
+   */
+  constraints?: Record<string, AstGrepRule>
   /**
    * The language to parse the code as.
    * Required for accurate AST parsing.
@@ -80,167 +99,241 @@ export type AstGrepFilterConfig = {
   /**
    * The pattern to match against. Can be a simple string pattern
    * or an object with context and selector for precise matching.
+   * Shorthand for a `rule` with only a `pattern`; with a `rule` as well, matches must satisfy both.
    */
-  pattern: AstGrepPatternObject | string
+  pattern?: AstGrepPatternObject | string
+  /**
+   * A full ast-grep rule, for matches that a single pattern can't express.
+   * See https://ast-grep.github.io/reference/rule.html for the rule language.
+   */
+  rule?: AstGrepRule
+  /**
+   * New metavariables derived from captured ones.
+   */
+  transform?: Record<string, AstGrepTransformation | string>
   /**
    * Discriminant tag identifying this as an ast-grep filter.
    */
   type: 'ast-grep'
+  /**
+   * Named rules that `matches` can refer to.
+   */
+  utils?: Record<string, AstGrepRule>
 }
 
-/**
- * Run ast-grep command with stdin input and return stdout.
- */
-function runAstGrep(args: string[], input: string): Promise<string> {
-  return new Promise((resolve, reject) => {
-    const proc = spawn('ast-grep', args, {
-      stdio: ['pipe', 'pipe', 'pipe'],
-    })
-
-    let stdout = ''
-    let stderr = ''
-
-    proc.stdout.on('data', (data: Buffer) => {
-      stdout += data.toString()
-    })
-
-    proc.stderr.on('data', (data: Buffer) => {
-      stderr += data.toString()
-    })
-
-    proc.on('close', (code) => {
-      // ast-grep run returns 0 for no matches, 1 for matches found
-      // ast-grep scan returns 0 for success, 1 for matches (when used as linter)
-      // We accept both as valid responses as long as we have output or no errors
-      if (code === 0 || code === 1 || stdout) {
-        resolve(stdout)
-      } else {
-        reject(new Error(`ast-grep exited with code ${code}: ${stderr}`))
-      }
-    })
+// Lazy-loaded ast-grep bindings
+type AstGrepModule = typeof import('@ast-grep/napi')
+let astGrepModule: null | Promise<AstGrepModule> = null
 
-    proc.on('error', (err) => {
-      if ((err as NodeJS.ErrnoException).code === 'ENOENT') {
-        reject(new Error('ast-grep command not found. Please install it: npm install -g @ast-grep/cli'))
-      } else {
-        reject(err)
-      }
-    })
+// Languages @ast-grep/napi doesn't bundle, loaded from their grammar packages
+const DYNAMIC_LANGUAGE_PACKAGES: Record<string, string> = {
+  c: '@ast-grep/lang-c',
+  cpp: '@ast-grep/lang-cpp',
+  go: '@ast-grep/lang-go',
+  java: '@ast-grep/lang-java',
+  json: '@ast-grep/lang-json',
+  python: '@ast-grep/lang-python',
+  rust: '@ast-grep/lang-rust',
+}
 
-    proc.stdin.write(input)
-    proc.stdin.end()
-  })
+// Language names accepted in config, including the aliases the ast-grep CLI accepts (see AstGrepLanguage)
+const LANGUAGE_ALIASES: Record<string, string> = {
+  'c++': 'cpp',
+  cc: 'cpp',
+  css: 'Css',
+  cxx: 'cpp',
+  golang: 'go',
+  html: 'Html',
+  javascript: 'JavaScript',
+  js: 'JavaScript',
+  jsx: 'JavaScript',
+  py: 'python',
+  rs: 'rust',
+  ts: 'TypeScript',
+  tsx: 'Tsx',
+  typescript: 'TypeScript',
 }
 
 /**
- * Build inline rule YAML for ast-grep scan command.
- * Used for pattern objects with context and selector.
+ * Load the ast-grep bindings and register the additional languages once.
  */
-function buildInlineRule(language: string, pattern: AstGrepPatternObject): string {
-  return `id: distill-filter
-language: ${language}
-rule:
-  pattern:
-    context: "${pattern.context}"
-    selector: ${pattern.selector}
-`
+function getAstGrep(): Promise<AstGrepModule> {
+  astGrepModule ??= (async () => {
+    const napi = await import('@ast-grep/napi')
+    const languages = await Promise.all(
+      Object.entries(DYNAMIC_LANGUAGE_PACKAGES).map(async ([name, packageName]) => {
+        const grammar = await import(packageName)
+        return [name, grammar.default ?? grammar]
+      }),
+    )
+    napi.registerDynamicLanguage(Object.fromEntries(languages))
+    return napi
+  })()
+
+  return astGrepModule
 }
 
 /**
- * Extract matched text from ast-grep JSON output.
+ * Resolve a configured language name to the name ast-grep registers it under.
+ *
+ * @throws Error if the language is not supported
  */
-interface AstGrepMatch {
-  metaVariables?: {
-    multi?: Record<string, {text: string}>
-    single?: Record<string, {text: string}>
-    transformed?: Record<string, {text: string}>
-  }
-  text: string
+function resolveLanguage(language: string): string {
+  const name = language.toLowerCase()
+  if (name in DYNAMIC_LANGUAGE_PACKAGES) return name
+  if (name in LANGUAGE_ALIASES) return LANGUAGE_ALIASES[name]
+
+  const supported = [...Object.keys(DYNAMIC_LANGUAGE_PACKAGES), ...Object.keys(LANGUAGE_ALIASES)].sort()
+  throw new Error(`Unsupported ast-grep language "${language}". Supported languages: ${supported.join(', ')}`)
 }
 
 /**
- * Extract matched text and context from ast-grep JSON output.
+ * Build the rule config ast-grep matches with.
+ * Rules are passed as objects, so patterns may contain any characters.
+ *
+ * @throws Error if the config has neither a pattern nor a rule
  */
-function extractMatchedText(jsonOutput: string): {context: Record<string, string>[][]; text: string} {
-  if (!jsonOutput.trim()) {
-    return {context: [], text: ''}
+function buildMatcher(config: AstGrepFilterConfig): NapiConfig {
+  const {constraints, pattern, rule, transform, utils} = config
+  if (!rule && pattern === undefined) {
+    throw new Error('ast-grep watch requires a pattern or a rule')
   }
 
-  try {
-    const matches = JSON.parse(jsonOutput) as AstGrepMatch[]
-    const text = matches.map((m) => m.text).join('\n\n')
+  return {
+    // A pattern next to a rule narrows it, like `all` would
+    rule: rule && pattern !== undefined ? {all: [{pattern}, rule]} : (rule ?? {pattern}),
+    ...(constraints ? {constraints} : {}),
+    ...(transform ? {transform} : {}),
+    ...(utils ? {utils} : {}),
+  } as NapiConfig
+}
 
-    // Extract meta-variables as context
-    // eslint-disable-next-line @typescript-eslint/no-explicit-any
-    const contexts: Record<string, any>[] = []
+/**
+ * Collect the metavariable names a config can capture, e.g. `NAME` and `ARGS` in `$NAME($$$ARGS)`.
+ * Patterns are collected from anywhere in the rule, constraints and utils.
+ */
+function metaVariableNames(config: AstGrepFilterConfig): {multi: string[]; single: string[]; transformed: string[]} {
+  const multi = new Set<string>()
+  const single = new Set<string>()
 
-    for (const match of matches) {
-      if (match.metaVariables) {
-        const context: Record<string, string> = {}
+  const collect = (pattern: AstGrepPatternObject | string) => {
+    const source = typeof pattern === 'string' ? pattern : pattern.context
+    for (const match of source.matchAll(/(\$\$\$|\$)([A-Z_][A-Z0-9_]*)/g)) {
+      // Names starting with `_` match without capturing
+      if (match[2].startsWith('_')) continue
 
-        // Helper to merge variables
+      if (match[1] === '$$$') {
+        multi.add(match[2])
+      } else {
+        single.add(match[2])
+      }
+    }
+  }
 
-        const extractVars = (vars?: Record<string, {text: string}>) => {
-          if (!vars) return
-          for (const [key, value] of Object.entries(vars)) {
-            // eslint-disable-next-line @typescript-eslint/no-explicit-any
-            context[key] = (value as any).text
-          }
+  const walk = (value: unknown): void => {
+    if (Array.isArray(value)) {
+      for (const item of value) walk(item)
+    } else if (value && typeof value === 'object') {
+      for (const [key, child] of Object.entries(value)) {
+        if (key === 'pattern') {
+          collect(child as AstGrepPatternObject | string)
+        } else {
+          walk(child)
         }
+      }
+    }
+  }
 
-        extractVars(match.metaVariables.single)
-        extractVars(match.metaVariables.multi)
-        extractVars(match.metaVariables.transformed)
+  if (config.pattern !== undefined) collect(config.pattern)
+  walk([config.rule, config.constraints, config.utils])
 
-        if (Object.keys(context).length > 0) {
-          contexts.push(context)
-        }
-      }
+  return {multi: [...multi], single: [...single], transformed: Object.keys(config.transform ?? {})}
+}
+
+/**
+ * Extract the text of captured metavariables from a match.
+ */
+function extractMetaVariables(
+  node: SgNode,
+  content: string,
+  names: ReturnType<typeof metaVariableNames>,
+): Record<string, string> {
+  const context: Record<string, string> = {}
+
+  for (const name of names.single) {
+    const captured = node.getMatch(name)
+    if (captured) context[name] = captured.text()
+  }
+
+  for (const name of names.multi) {
+    const captured = node.getMultipleMatches(name)
+    if (captured.length > 0) {
+      // Take the source span so separators and whitespace between the nodes are kept
+      context[name] = content.slice(captured[0].range().start.index, captured.at(-1)!.range().end.index)
     }
+  }
+
+  for (const name of names.transformed) {
+    const transformed = node.getTransformed(name)
+    if (transformed !== null) context[name] = transformed
+  }
+
+  return context
+}
+
+/**
+ * Find all matches of a pattern or rule in source code.
+ */
+async function findMatches(content: string, config: AstGrepFilterConfig): Promise<ExtractedContent> {
+  const {parse} = await getAstGrep()
+  const matches = parse(resolveLanguage(config.language), content).root().findAll(buildMatcher(config))
 
+  const names = metaVariableNames(config)
+  const locate = createRangeLocator(content)
+  const sourceMatches = matches.map((match): SourceMatch => {
+    const {end, start} = match.range()
+    const captures = extractMetaVariables(match, content, names)
     return {
-      context: [contexts],
-      text,
+      ...(Object.keys(captures).length > 0 ? {captures} : {}),
+      range: locate(start.index, end.index),
+      text: match.text(),
     }
-  } catch {
-    // If parsing fails, return empty
-    return {context: [], text: ''}
+  })
+
+  const contexts = sourceMatches.flatMap((match) => (match.captures ? [match.captures] : []))
+  return {
+    context: contexts.length > 0 ? [contexts] : [],
+    matches: sourceMatches,
+    text: matches.map((match) => match.text()).join('\n\n'),
   }
 }
 
 /**
  * ast-grep filter for extracting AST nodes using pattern matching.
- * Uses the ast-grep CLI tool to parse and search code.
+ * Runs ast-grep in-process through its native bindings.
  */
 export const astGrepFilter: FilterApplier<AstGrepFilterConfig> = {
   async apply(versions: FileVersions, config: AstGrepFilterConfig): Promise<FilterResult | null> {
-    const {language, pattern} = config
-
     // Determine language
-    if (!language) {
+    if (!config.language) {
       throw new Error('ast-grep filter requires a language to be specified')
     }
 
-    const extractNodes = async (
-      content: null | string,
-    ): Promise<{context: Record<string, string>[][]; text: string}> => {
+    const extractNodes = async (content: null | string): Promise<ExtractedContent> => {
       if (!content) return {context: [], text: ''}
 
-      let args: string[]
+      return findMatches(content, config)
+    }
 
-      if (typeof pattern === 'string') {
-        // Simple string pattern - use the simpler "run" command
-        args = ['run', '-p', pattern, '--lang', language, '--json', '--stdin']
-      } else {
-        // Pattern object with context/selector - use "scan" with inline rules
-        const inlineRule = buildInlineRule(language, pattern)
-        args = ['scan', '--inline-rules', inlineRule, '--json', '--stdin']
-      }
+    return processFilter(versions, extractNodes)
+  },
 
-      const jsonOutput = await runAstGrep(args, content)
-      return extractMatchedText(jsonOutput)
+  async validate(config: AstGrepFilterConfig): Promise<void> {
+    if (!config.language) {
+      throw new Error('ast-grep filter requires a language to be specified')
     }
 
-    return processFilter(versions, extractNodes)
+    // Matching against empty input surfaces unknown languages, unparseable patterns and invalid rules
+    await findMatches('', config)
   },
 }
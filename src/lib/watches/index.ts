@@ -3,9 +3,12 @@ import type {FilterResult} from './types.js'
 
 import {astGrepFilter, type AstGrepFilterConfig} from './ast-grep.js'
 import {jqFilter, type JqFilterConfig} from './jq.js'
+import {validatePathExpression} from './paths.js'
 import {regexFilter, type RegexFilterConfig} from './regex.js'
+import {tomlFilter, type TomlFilterConfig} from './toml.js'
 import {tsqFilter, type TsqFilterConfig} from './tsq.js'
-import {xpathFilter, type XPathFilterConfig} from './xpath.js'
+import {validateNodeSelection, xpathFilter, type XPathFilterConfig} from './xpath.js'
+import {yamlFilter, type YamlFilterConfig} from './yaml.js'
 
 // Re-export types from individual watch implementations
 export type {AstGrepFilterConfig, AstGrepPatternObject} from './ast-grep.js'
@@ -14,11 +17,15 @@ export type {JqFilterConfig} from './jq.js'
 export {jqFilter} from './jq.js'
 export type {RegexFilterConfig} from './regex.js'
 export {regexFilter} from './regex.js'
+export type {TomlFilterConfig} from './toml.js'
+export {tomlFilter} from './toml.js'
 export type {TsqFilterConfig} from './tsq.js'
 export {tsqFilter} from './tsq.js'
 export type {FilterApplier, FilterResult} from './types.js'
 export type {XPathFilterConfig} from './xpath.js'
 export {xpathFilter} from './xpath.js'
+export type {YamlFilterConfig} from './yaml.js'
+export {yamlFilter} from './yaml.js'
 
 /**
  * Union type of all supported watch configurations (extraction part only, without include).
@@ -28,8 +35,10 @@ export type WatchExtractionConfig =
   | AstGrepFilterConfig
   | JqFilterConfig
   | RegexFilterConfig
+  | TomlFilterConfig
   | TsqFilterConfig
   | XPathFilterConfig
+  | YamlFilterConfig
 
 /**
  * All supported watch type names.
@@ -61,6 +70,10 @@ export async function applyWatch(
       return regexFilter.apply(versions, watch)
     }
 
+    case 'toml': {
+      return tomlFilter.apply(versions, watch)
+    }
+
     case 'tsq': {
       return tsqFilter.apply(versions, watch, filePath)
     }
@@ -69,6 +82,10 @@ export async function applyWatch(
       return xpathFilter.apply(versions, watch)
     }
 
+    case 'yaml': {
+      return yamlFilter.apply(versions, watch)
+    }
+
     default: {
       // TypeScript exhaustiveness check
       const exhaustiveCheck: never = watch
@@ -76,3 +93,63 @@ export async function applyWatch(
     }
   }
 }
+
+/**
+ * Check a watch configuration without applying it, e.g. by compiling its query.
+ * Uses the same routing as applyWatch.
+ *
+ * @param filePath - Sample path used for language detection when the watch has no explicit language
+ * @throws Error describing why the watch cannot be applied
+ */
+export async function validateWatch(watch: WatchExtractionConfig, filePath?: string): Promise<void> {
+  switch (watch.type) {
+    case 'ast-grep': {
+      return astGrepFilter.validate(watch, filePath)
+    }
+
+    case 'jq': {
+      return jqFilter.validate(watch)
+    }
+
+    case 'regex': {
+      return regexFilter.validate(watch)
+    }
+
+    case 'toml': {
+      return tomlFilter.validate(watch)
+    }
+
+    case 'tsq': {
+      return tsqFilter.validate(watch, filePath)
+    }
+
+    case 'xpath': {
+      return xpathFilter.validate(watch)
+    }
+
+    case 'yaml': {
+      return yamlFilter.validate(watch)
+    }
+
+    default: {
+      const exhaustiveCheck: never = watch
+      throw new Error(`Unsupported watch type: ${(exhaustiveCheck as WatchExtractionConfig).type}`)
+    }
+  }
+}
+
+/**
+ * Check that a watch locates its matches, which a `scope` other than `file` narrows to the changed lines.
+ * Catches what can be told without a file: jq, yaml and toml queries that compute their output,
+ * and XPath expressions that evaluate to a string, number or boolean.
+ *
+ * @throws Error describing why the watch cannot locate its matches
+ */
+export async function validateScope(watch: WatchExtractionConfig): Promise<void> {
+  // regex, tsq and ast-grep matches always have locations
+  if (watch.type === 'jq' || watch.type === 'toml' || watch.type === 'yaml') {
+    await validatePathExpression(watch.query)
+  } else if (watch.type === 'xpath') {
+    validateNodeSelection(watch)
+  }
+}
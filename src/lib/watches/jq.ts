@@ -1,11 +1,14 @@
+import * as jq from 'jq-wasm'
+
 import type {FileVersions} from '../diff/parser.js'
 import type {FilterApplier, FilterResult} from './types.js'
 
-import {createFilterResult, runWithStdin} from './utils.js'
+import {createYamlPathLocator, setQueryMatches} from './paths.js'
+import {createFilterResult} from './utils.js'
 
 /**
  * Configuration for the jq filter.
- * Uses the jq command-line tool to extract/transform JSON content.
+ * Uses an embedded build of jq to extract/transform JSON content, so no jq binary is required.
  *
  * @example
  * ```yaml
@@ -40,14 +43,50 @@ export interface JqFilterConfig {
 
 /**
  * Run jq with the given query on the input content.
+ * The input may be a stream of several JSON values, in which case the query runs on each.
+ *
+ * @param options.sortKeys - Sort object keys in the output, for artifacts that ignore key order
+ * @throws Error with jq's message if the query fails on any input, e.g. invalid JSON or indexing a string
+ */
+export async function runJq(content: string, query: string, options: {sortKeys?: boolean} = {}): Promise<string> {
+  const {exitCode, stderr, stdout} = await jq.raw(content, query, options.sortKeys ? ['--sort-keys'] : [])
+
+  if (exitCode !== 0) {
+    throw new Error(formatJqError(stderr, exitCode))
+  }
+
+  // Match the jq CLI, which terminates every output value with a newline
+  return stdout && !stdout.endsWith('\n') ? `${stdout}\n` : stdout
+}
+
+/**
+ * Parenthesize a query to embed it in a larger jq program, like `path(${embedQuery(query)})`.
+ * Newlines keep a trailing `# comment` in the query from swallowing the closing paren.
+ */
+export function embedQuery(query: string): string {
+  return `(\n${query}\n)`
+}
+
+/**
+ * Compile a jq query without running it.
+ *
+ * @throws Error with jq's message if the query does not compile
  */
-async function runJq(content: string, query: string): Promise<string> {
-  return runWithStdin('jq', [query], content)
+export async function compileJq(query: string): Promise<void> {
+  // `empty` short-circuits evaluation, so this only compiles the query
+  const {exitCode, stderr} = await jq.raw('', `empty | ${embedQuery(query)}`, ['-n'])
+
+  if (exitCode !== 0) {
+    throw new Error(formatJqError(stderr, exitCode))
+  }
+}
+
+function formatJqError(stderr: string, exitCode: number): string {
+  return stderr.trim() || `jq exited with code ${exitCode}`
 }
 
 /**
  * JQ filter for processing JSON content.
- * Uses the jq command-line tool to extract/transform JSON.
  */
 export const jqFilter: FilterApplier<JqFilterConfig> = {
   async apply(versions: FileVersions, config: JqFilterConfig): Promise<FilterResult | null> {
@@ -59,6 +98,19 @@ export const jqFilter: FilterApplier<JqFilterConfig> = {
     const leftArtifact = versions.oldContent ? await runJq(versions.oldContent, config.query) : ''
     const rightArtifact = versions.newContent ? await runJq(versions.newContent, config.query) : ''
 
-    return createFilterResult(leftArtifact, rightArtifact, false)
+    const result = await createFilterResult(leftArtifact, rightArtifact, false)
+    if (result) {
+      // JSON parses as YAML, which keeps the positions of its values
+      await setQueryMatches(result, versions, config.query, (content) => ({
+        locate: createYamlPathLocator(content),
+        stream: content,
+      }))
+    }
+
+    return result
+  },
+
+  async validate(config: JqFilterConfig): Promise<void> {
+    await compileJq(config.query)
   },
 }
@@ -0,0 +1,237 @@
+import * as jq from 'jq-wasm'
+import {isNode, parseAllDocuments} from 'yaml'
+
+import type {SourceMatch, SourceRange} from '../configuration/config.js'
+import type {FileVersions} from '../diff/parser.js'
+import type {FilterResult} from './types.js'
+
+import {embedQuery} from './jq.js'
+import {createRangeLocator} from './utils.js'
+
+/** A path into a JSON value, as returned by jq's `path(f)` */
+export type JsonPath = Array<number | string>
+
+/**
+ * Find where the value at a path of one of the queried documents is in the source file.
+ */
+export type PathLocator = (path: JsonPath, document: number) => SourceRange | undefined
+
+/**
+ * Content as a jq filter queries it: a stream of JSON values, and how to find them in the source file.
+ */
+export interface QueriedContent {
+  /** Absent when the source cannot be mapped back, e.g. a stream of several JSON values */
+  locate?: PathLocator
+  stream: string
+}
+
+/**
+ * Set the matches of each side of a jq, yaml or toml filter result to the values its query selects.
+ * Matches can only be found when the query is a path expression like `.dependencies` or
+ * `select(.kind == "Deployment") | .spec`; the result is left without matches otherwise.
+ *
+ * @param options.sortKeys - Sort object keys in the match text, like the artifacts of the filter
+ */
+export async function setQueryMatches(
+  result: FilterResult,
+  versions: FileVersions,
+  query: string,
+  toQueried: (content: string) => QueriedContent,
+  options: {sortKeys?: boolean} = {},
+): Promise<void> {
+  const locate = async (content: null | string) =>
+    content ? locateQueryMatches(toQueried(content), query, options) : []
+
+  const [left, right] = await Promise.all([locate(versions.oldContent), locate(versions.newContent)])
+  // Matches on one side only would make everything on the other side look out of scope
+  if (left && right) {
+    result.left.matches = left
+    result.right.matches = right
+  }
+}
+
+/**
+ * Check that a jq query selects values by path, as far as running it on `null` tells.
+ * Queries that fail on `null` before computing a value, like `.dependencies | keys`, are not caught.
+ *
+ * @throws Error with jq's message if the query computes its output
+ */
+export async function validatePathExpression(query: string): Promise<void> {
+  const program = `try ([null | path(${embedQuery(query)})] | empty)
+    catch (tostring | select(startswith("Invalid path expression")))`
+  const {exitCode, stdout} = await jq.raw('', program, ['-n', '-r'])
+  // Queries that do not compile are reported when the watch is validated
+  if (exitCode === 0 && stdout.trim()) {
+    throw new Error(`${stdout.trim()}; select values by path, like ".dependencies"`)
+  }
+}
+
+/**
+ * Locate the values a jq query selects, or return undefined when the query is not a path expression.
+ */
+async function locateQueryMatches(
+  content: QueriedContent,
+  query: string,
+  options: {sortKeys?: boolean},
+): Promise<SourceMatch[] | undefined> {
+  if (!content.locate) return undefined
+
+  const program = `try [[inputs] | to_entries[] | .key as $document | .value | path(${embedQuery(query)}) as $path
+    | [$document, $path, getpath($path)]] catch null`
+  const flags = ['-n', '-c', ...(options.sortKeys ? ['--sort-keys'] : [])]
+  const {exitCode, stdout} = await jq.raw(content.stream, program, flags)
+  if (exitCode !== 0) return undefined
+
+  const found = JSON.parse(stdout) as Array<[number, JsonPath, unknown]> | null
+  if (!found) return undefined
+
+  const matches: SourceMatch[] = []
+  for (const [document, path, value] of found) {
+    const range = content.locate(path, document)
+    // Indent like jq's output, so the text of a match reads like the artifact
+    if (range) matches.push({range, text: JSON.stringify(value, null, 2)})
+  }
+
+  return matches
+}
+
+/**
+ * Locate paths in YAML, or JSON as a subset of it, from the positions of the parsed nodes.
+ * Empty documents are skipped, as in the stream the yaml filter queries.
+ * Returns undefined when the content does not parse, e.g. a stream of JSON values without `---` separators.
+ */
+export function createYamlPathLocator(content: string): PathLocator | undefined {
+  const documents = parseAllDocuments(content)
+  if (documents.some((doc) => doc.errors.length > 0)) return undefined
+
+  const nonEmpty = documents.filter((doc) => doc.contents !== null)
+  const toRange = createRangeLocator(content)
+
+  return (path, document) => {
+    const node = path.length === 0 ? nonEmpty[document]?.contents : nonEmpty[document]?.getIn(path, true)
+    if (!isNode(node) || !node.range) return undefined
+
+    return toRange(node.range[0], node.range[1])
+  }
+}
+
+// `[table]` and `[[array of tables]]` headers, and the dotted key of a `key = value` line
+const TOML_KEY = String.raw`(?:[\w-]+|"(?:[^"\\]|\\.)*"|'[^']*')(?:\s*\.\s*(?:[\w-]+|"(?:[^"\\]|\\.)*"|'[^']*'))*`
+const TOML_ARRAY_HEADER = new RegExp(String.raw`^\[\[\s*(${TOML_KEY})\s*\]\]\s*(?:#.*)?$`)
+const TOML_TABLE_HEADER = new RegExp(String.raw`^\[\s*(${TOML_KEY})\s*\]\s*(?:#.*)?$`)
+const TOML_KEY_VALUE = new RegExp(String.raw`^(${TOML_KEY})\s*=(.*)$`)
+
+/**
+ * Locate paths in TOML by the table headers and keys that define them.
+ * A path inside a value, like an element of an array, is located at the key holding it,
+ * and a table defined by a header spans the header and its keys.
+ */
+export function createTomlPathLocator(content: string): PathLocator {
+  const lines = content.split('\n').map((line) => line.replace(/\r$/, ''))
+  const entries: Array<{end: number; path: JsonPath; start: number; table: boolean}> = []
+  const arrayTables = new Map<string, number>()
+  let table: JsonPath = []
+  // Open brackets or the delimiter of a multi-line string that continue the value of the last key
+  let depth = 0
+  let multiline: null | string = null
+
+  // Insert the current index of each array of tables a header is nested in
+  const resolve = (keys: string[]): JsonPath => {
+    const path: JsonPath = []
+    for (const key of keys) {
+      path.push(key)
+      const index = arrayTables.get(JSON.stringify(path))
+      if (index !== undefined) path.push(index)
+    }
+
+    return path
+  }
+
+  for (const [index, line] of lines.entries()) {
+    const trimmed = line.trim()
+
+    if (multiline || depth > 0) {
+      entries.at(-1)!.end = index
+      if (multiline) {
+        if (trimmed.includes(multiline)) multiline = null
+      } else {
+        depth += bracketDepth(trimmed)
+      }
+
+      continue
+    }
+
+    const arrayHeader = TOML_ARRAY_HEADER.exec(trimmed)
+    const tableHeader = TOML_TABLE_HEADER.exec(trimmed)
+    const keyValue = TOML_KEY_VALUE.exec(trimmed)
+
+    if (arrayHeader) {
+      const keys = parseTomlKey(arrayHeader[1])
+      const path = [...resolve(keys.slice(0, -1)), keys.at(-1)!]
+      const position = (arrayTables.get(JSON.stringify(path)) ?? -1) + 1
+      arrayTables.set(JSON.stringify(path), position)
+      table = [...path, position]
+      entries.push({end: index, path: table, start: index, table: true})
+    } else if (tableHeader) {
+      table = resolve(parseTomlKey(tableHeader[1]))
+      entries.push({end: index, path: table, start: index, table: true})
+    } else if (keyValue) {
+      entries.push({end: index, path: [...table, ...parseTomlKey(keyValue[1])], start: index, table: false})
+      const value = keyValue[2]
+      multiline = ['"""', "'''"].find((delimiter) => value.split(delimiter).length % 2 === 0) ?? null
+      depth = multiline ? 0 : bracketDepth(value)
+    }
+  }
+
+  // Tables span their keys, up to the next header
+  for (const [index, entry] of entries.entries()) {
+    if (!entry.table) continue
+    const next = entries.slice(index + 1).find((other) => other.table)
+    const keys = entries.slice(index + 1, next ? entries.indexOf(next) : undefined)
+    entry.end = Math.max(entry.end, ...keys.map((key) => key.end))
+  }
+
+  return (path) => {
+    let best: (typeof entries)[number] | undefined
+    for (const entry of entries) {
+      const isPrefix = entry.path.length <= path.length && entry.path.every((key, i) => key === path[i])
+      if (isPrefix && entry.path.length > (best?.path.length ?? 0)) best = entry
+    }
+
+    if (!best) return undefined
+
+    const indent = lines[best.start].length - lines[best.start].trimStart().length
+    return {
+      endColumn: lines[best.end].length + 1,
+      endLine: best.end + 1,
+      startColumn: indent + 1,
+      startLine: best.start + 1,
+    }
+  }
+}
+
+/**
+ * Split a dotted TOML key into its parts, unquoting quoted ones.
+ */
+function parseTomlKey(key: string): string[] {
+  const parts = key.match(/"(?:[^"\\]|\\.)*"|'[^']*'|[\w-]+/g) ?? []
+  return parts.map((part) => {
+    if (part.startsWith('"')) return JSON.parse(part) as string
+    if (part.startsWith("'")) return part.slice(1, -1)
+    return part
+  })
+}
+
+/**
+ * Count the brackets and braces a line of a TOML value opens but does not close, ignoring strings and comments.
+ */
+function bracketDepth(value: string): number {
+  const code = value.replaceAll(/"(?:[^"\\]|\\.)*"|'[^']*'/g, '').replace(/#.*$/, '')
+  let depth = 0
+  for (const char of code) {
+    if (char === '[' || char === '{') depth++
+    else if (char === ']' || char === '}') depth--
+  }
+
+  return depth
+}
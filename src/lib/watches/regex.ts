@@ -1,7 +1,7 @@
 import type {FileVersions} from '../diff/parser.js'
 import type {FilterApplier, FilterResult} from './types.js'
 
-import {processFilter} from './utils.js'
+import {createRangeLocator, type ExtractedContent, processFilter} from './utils.js'
 
 /**
  * Configuration for the regex filter.
@@ -58,19 +58,16 @@ export interface RegexFilterConfig {
  */
 export const regexFilter: FilterApplier<RegexFilterConfig> = {
   async apply(versions: FileVersions, config: RegexFilterConfig): Promise<FilterResult | null> {
-    const {flags = '', pattern} = config
+    const regex = compileRegex(config)
 
-    // Always include 'g' flag for global matching, and 'm' for multiline
-    const effectiveFlags = flags.includes('g') ? flags : `${flags}gm`
-    const regex = new RegExp(pattern, effectiveFlags)
-
-    const extractMatches = (content: null | string): {context: Record<string, string>[][]; text: string} => {
+    const extractMatches = (content: null | string): ExtractedContent => {
       if (!content) return {context: [], text: ''}
 
       const matches = [...content.matchAll(regex)]
       if (matches.length === 0) return {context: [], text: ''}
 
       const text = matches.map((m) => m[0]).join('\n')
+      const locate = createRangeLocator(content)
 
       // Extract groups from each match
       // eslint-disable-next-line @typescript-eslint/no-explicit-any
@@ -84,10 +81,30 @@ export const regexFilter: FilterApplier<RegexFilterConfig> = {
 
       return {
         context: [contexts],
+        matches: matches.map((m) => ({
+          ...(m.groups ? {captures: {...m.groups}} : {}),
+          range: locate(m.index, m.index + m[0].length),
+          text: m[0],
+        })),
         text,
       }
     }
 
     return processFilter(versions, extractMatches)
   },
+
+  async validate(config: RegexFilterConfig): Promise<void> {
+    compileRegex(config)
+  },
+}
+
+/**
+ * Compile the configured pattern with the 'g' and 'm' flags always applied.
+ */
+function compileRegex(config: RegexFilterConfig): RegExp {
+  const {flags = '', pattern} = config
+
+  // Always include 'g' flag for global matching, and 'm' for multiline
+  const effectiveFlags = flags.includes('g') ? flags : `${flags}gm`
+  return new RegExp(pattern, effectiveFlags)
 }
@@ -0,0 +1,91 @@
+import {parse} from 'smol-toml'
+
+import type {FileVersions} from '../diff/parser.js'
+import type {FilterApplier, FilterResult} from './types.js'
+
+import {compileJq, runJq} from './jq.js'
+import {createTomlPathLocator, setQueryMatches} from './paths.js'
+import {createFilterResult} from './utils.js'
+
+/**
+ * Configuration for the toml filter.
+ * Parses TOML manifests such as Cargo.toml and pyproject.toml and applies a jq query to the parsed document.
+ * Dotted keys, inline tables and `[table]` headers that describe the same data give the same output.
+ *
+ * @example
+ * ```yaml
+ * # Shared dependency versions of a Cargo workspace
+ * filters:
+ *   - type: toml
+ *     query: ".workspace.dependencies"
+ * ```
+ *
+ * @example
+ * ```yaml
+ * # Build requirements of a Python project
+ * filters:
+ *   - type: toml
+ *     query: ".\"build-system\".requires"
+ * ```
+ */
+export interface TomlFilterConfig {
+  /**
+   * The jq query expression to apply to the parsed TOML.
+   * See https://jqlang.github.io/jq/manual/ for syntax reference.
+   *
+   * @example ".dependencies"
+   * @example ".project.dependencies"
+   * @example ".workspace.members"
+   */
+  query: string
+
+  /**
+   * Discriminant tag identifying this as a toml filter.
+   */
+  type: 'toml'
+}
+
+/**
+ * Parse TOML content into a JSON document.
+ * Dates and times are serialized as strings.
+ *
+ * @throws Error if the TOML cannot be parsed
+ */
+export function tomlToJson(content: string): string {
+  try {
+    return JSON.stringify(parse(content))
+  } catch (error) {
+    throw new Error(`Invalid TOML: ${(error as Error).message}`)
+  }
+}
+
+/**
+ * TOML filter for processing TOML content with jq queries.
+ */
+export const tomlFilter: FilterApplier<TomlFilterConfig> = {
+  async apply(versions: FileVersions, config: TomlFilterConfig): Promise<FilterResult | null> {
+    // If both are null, nothing to filter
+    if (versions.oldContent === null && versions.newContent === null) {
+      return null
+    }
+
+    // Empty files are treated like missing ones, as in the jq and yaml filters
+    const query = async (content: null | string) =>
+      content ? runJq(tomlToJson(content), config.query, {sortKeys: true}) : ''
+
+    const leftArtifact = await query(versions.oldContent)
+    const rightArtifact = await query(versions.newContent)
+
+    const result = await createFilterResult(leftArtifact, rightArtifact, false)
+    if (result) {
+      const toQueried = (content: string) => ({locate: createTomlPathLocator(content), stream: tomlToJson(content)})
+      await setQueryMatches(result, versions, config.query, toQueried, {sortKeys: true})
+    }
+
+    return result
+  },
+
+  async validate(config: TomlFilterConfig): Promise<void> {
+    await compileJq(config.query)
+  },
+}
@@ -1,10 +1,11 @@
 import {extname} from 'node:path'
 
+import type {SourceMatch} from '../configuration/config.js'
 import type {FileVersions} from '../diff/parser.js'
 import type {FilterApplier, FilterResult} from './types.js'
 
 import {getLanguageForExtension, initTreeSitter, type SupportedExtension} from '../tree-sitter.js'
-import {processFilter} from './utils.js'
+import {createRangeLocator, type ExtractedContent, processFilter} from './utils.js'
 
 export type TsqSupportedExtension = SupportedExtension
 
@@ -92,27 +93,13 @@ export interface TsqFilterConfig {
  */
 export const tsqFilter: FilterApplier<TsqFilterConfig> = {
   async apply(versions: FileVersions, config: TsqFilterConfig, filePath?: string): Promise<FilterResult | null> {
-    const {capture, language: languageOverride, query: queryString} = config
-
-    // Determine file extension for language detection
-    const ext = languageOverride || (filePath ? extname(filePath) : null)
-    if (!ext) {
-      throw new Error('TSQ filter requires a file extension (either from file path or as "language" config property)')
-    }
-
-    // Initialize tree-sitter and get module
-    const ts = await initTreeSitter()
-
-    // Get the language
-    const language = await getLanguageForExtension(ext, ts)
-    if (!language) {
-      throw new Error(`Unsupported language for extension: ${ext}`)
-    }
+    const {capture, query: queryString} = config
+    const {language, ts} = await loadLanguage(config, filePath)
 
     // Get Parser and Query constructors from ts module
     const {Parser, Query} = ts
 
-    const extractNodes = (content: null | string): {context: Record<string, string>[][]; text: string} => {
+    const extractNodes = (content: null | string): ExtractedContent => {
       if (!content) return {context: [], text: ''}
 
       try {
@@ -125,7 +112,9 @@ export const tsqFilter: FilterApplier<TsqFilterConfig> = {
         const query = new Query(language, queryString)
         const matches = query.matches(tree.rootNode)
 
+        const locate = createRangeLocator(content)
         const nodeTexts: string[] = []
+        const nodeMatches: SourceMatch[] = []
         const contexts: Record<string, string>[] = []
         const seen = new Set<number>()
 
@@ -149,15 +138,7 @@ export const tsqFilter: FilterApplier<TsqFilterConfig> = {
                   ),
               )
 
-          // 2. Add content to nodeTexts
-          for (const c of contentCaptures) {
-            if (!seen.has(c.node.id)) {
-              seen.add(c.node.id)
-              nodeTexts.push(c.node.text)
-            }
-          }
-
-          // 3. Context is everything else
+          // 2. Context is everything else
           // We exclude the captured content itself from the context to avoid redundancy/noise
           const matchContext: Record<string, string> = {}
           let hasContext = false
@@ -176,6 +157,19 @@ export const tsqFilter: FilterApplier<TsqFilterConfig> = {
           if (hasContext) {
             contexts.push(matchContext)
           }
+
+          // 3. Add content to nodeTexts, with the context of the match that found it
+          for (const c of contentCaptures) {
+            if (!seen.has(c.node.id)) {
+              seen.add(c.node.id)
+              nodeTexts.push(c.node.text)
+              nodeMatches.push({
+                ...(hasContext ? {captures: matchContext} : {}),
+                range: locate(c.node.startIndex, c.node.endIndex),
+                text: c.node.text,
+              })
+            }
+          }
         }
 
         // Clean up
@@ -185,6 +179,7 @@ export const tsqFilter: FilterApplier<TsqFilterConfig> = {
 
         return {
           context: [contexts], // Wrap in array to match expected SymbolicContext[][] structure if needed, or adjust types
+          matches: nodeMatches,
           text: nodeTexts.join('\n\n'),
         }
       } catch {
@@ -195,4 +190,34 @@ export const tsqFilter: FilterApplier<TsqFilterConfig> = {
 
     return processFilter(versions, extractNodes)
   },
+
+  async validate(config: TsqFilterConfig, filePath?: string): Promise<void> {
+    const {language, ts} = await loadLanguage(config, filePath)
+
+    // Query construction compiles the pattern and throws on syntax errors or unknown node types
+    const query = new ts.Query(language, config.query)
+    query.delete()
+  },
+}
+
+/**
+ * Initialize tree-sitter and load the language for the configured override or the file's extension.
+ */
+async function loadLanguage(config: TsqFilterConfig, filePath?: string) {
+  // Determine file extension for language detection
+  const ext = config.language || (filePath ? extname(filePath) : null)
+  if (!ext) {
+    throw new Error('TSQ filter requires a file extension (either from file path or as "language" config property)')
+  }
+
+  // Initialize tree-sitter and get module
+  const ts = await initTreeSitter()
+
+  // Get the language
+  const language = await getLanguageForExtension(ext, ts)
+  if (!language) {
+    throw new Error(`Unsupported language for extension: ${ext}`)
+  }
+
+  return {language, ts}
 }
@@ -1,3 +1,4 @@
+import type {SourceMatch} from '../configuration/config.js'
 import type {FileVersions} from '../diff/parser.js'
 
 /**
@@ -9,6 +10,8 @@ export interface FilterResult {
   diffText: string
   left: {
     artifact: string
+    captures?: Record<string, string>[]
+    matches?: SourceMatch[]
   }
   lineRange?: {
     end: number
@@ -16,6 +19,8 @@ export interface FilterResult {
   }
   right: {
     artifact: string
+    captures?: Record<string, string>[]
+    matches?: SourceMatch[]
   }
 }
 
@@ -32,4 +37,12 @@ export interface FilterApplier<TConfig> {
    * @returns FilterResult if there's a meaningful diff, null otherwise
    */
   apply(versions: FileVersions, config: TConfig, filePath?: string): Promise<FilterResult | null>
+
+  /**
+   * Check the filter configuration without applying it, e.g. by compiling its query.
+   * @param config - Filter-specific configuration
+   * @param filePath - Optional file path for language detection (used by tsq)
+   * @throws Error describing why the configuration cannot be applied
+   */
+  validate(config: TConfig, filePath?: string): Promise<void>
 }
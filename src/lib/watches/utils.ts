@@ -4,54 +4,48 @@ import {unlink, writeFile} from 'node:fs/promises'
 import {tmpdir} from 'node:os'
 import {join} from 'node:path'
 
+import type {SourceMatch, SourceRange} from '../configuration/config.js'
 import type {FileVersions} from '../diff/parser.js'
 import type {FilterResult} from './types.js'
 
 export interface ExtractedContent {
   // eslint-disable-next-line @typescript-eslint/no-explicit-any
   context: Record<string, any>[][]
+  /** Each extracted node or match with its position in the content */
+  matches?: SourceMatch[]
   text: string
 }
 
 export type Extractor = (content: null | string) => ExtractedContent | Promise<ExtractedContent>
 
 /**
- * Run a command with stdin input and return stdout.
+ * Create a function that converts string offsets in content into 1-based line/column ranges.
+ * Line starts are computed once, so converting many matches in the same content is cheap.
  */
-export function runWithStdin(command: string, args: string[], input: string): Promise<string> {
-  return new Promise((resolve, reject) => {
-    const proc = spawn(command, args, {
-      stdio: ['pipe', 'pipe', 'pipe'],
-    })
-
-    let stdout = ''
-    let stderr = ''
-
-    proc.stdout.on('data', (data: Buffer) => {
-      stdout += data.toString()
-    })
-
-    proc.stderr.on('data', (data: Buffer) => {
-      stderr += data.toString()
-    })
-
-    proc.on('close', (code) => {
-      if (code === 0) {
-        resolve(stdout)
-      } else if (stdout) {
-        // Some tools (like jq) return non-zero for certain operations
-        // but still produce valid output
-        resolve(stdout)
-      } else {
-        reject(new Error(`${command} exited with code ${code}: ${stderr}`))
-      }
-    })
+export function createRangeLocator(content: string): (start: number, end: number) => SourceRange {
+  const lineStarts = [0]
+  for (let i = content.indexOf('\n'); i !== -1; i = content.indexOf('\n', i + 1)) {
+    lineStarts.push(i + 1)
+  }
 
-    proc.on('error', reject)
+  const position = (offset: number) => {
+    // Binary search for the last line starting at or before the offset
+    let low = 0
+    let high = lineStarts.length - 1
+    while (low < high) {
+      const mid = Math.ceil((low + high) / 2)
+      if (lineStarts[mid] <= offset) low = mid
+      else high = mid - 1
+    }
+
+    return {column: offset - lineStarts[low] + 1, line: low + 1}
+  }
 
-    proc.stdin.write(input)
-    proc.stdin.end()
-  })
+  return (start, end) => {
+    const from = position(start)
+    const to = position(end)
+    return {endColumn: to.column, endLine: to.line, startColumn: from.column, startLine: from.line}
+  }
 }
 
 /**
@@ -155,9 +149,16 @@ export async function processFilter(versions: FileVersions, extractor: Extractor
 
   const result = await createFilterResult(left.text, right.text)
 
+  if (result) {
+    if (left.matches) result.left.matches = left.matches
+    if (right.matches) result.right.matches = right.matches
+  }
+
   if (result && allContexts.size > 0) {
     // eslint-disable-next-line @typescript-eslint/no-explicit-any
     result.context = [...allContexts].map((c) => JSON.parse(c) as any)
+    result.left.captures = left.context.flat()
+    result.right.captures = right.context.flat()
   }
 
   return result
@@ -1,10 +1,11 @@
 import {DOMParser, XMLSerializer} from '@xmldom/xmldom'
 import xpath from 'xpath'
 
+import type {SourceMatch} from '../configuration/config.js'
 import type {FileVersions} from '../diff/parser.js'
 import type {FilterApplier, FilterResult} from './types.js'
 
-import {createFilterResult} from './utils.js'
+import {createFilterResult, createRangeLocator, type ExtractedContent} from './utils.js'
 
 /**
  * Configuration for the xpath filter.
@@ -53,6 +54,72 @@ export interface XPathFilterConfig {
   type: 'xpath'
 }
 
+/**
+ * Check that an XPath expression selects nodes, which have locations, rather than evaluating to a string,
+ * number or boolean.
+ *
+ * @throws Error if the expression evaluates to a value
+ */
+export function validateNodeSelection(config: XPathFilterConfig): void {
+  const doc = new DOMParser().parseFromString('<root/>', 'text/xml')
+  const select = config.namespaces ? xpath.useNamespaces(config.namespaces) : xpath.select
+
+  let value: ReturnType<typeof select>
+  try {
+    value = select(config.expression, doc)
+  } catch {
+    // Expressions that do not evaluate are reported when the watch is validated
+    return
+  }
+
+  if (typeof value !== 'object') {
+    throw new Error(`the expression evaluates to a ${typeof value}; select nodes, like "//version"`)
+  }
+}
+
+/** Node with the start position xmldom records while parsing */
+interface LocatedNode {
+  columnNumber?: number
+  lineNumber?: number
+  nodeName?: string
+  nodeType?: number
+}
+
+const ELEMENT_NODE = 1
+
+/**
+ * Find the source range of a selected node from the start position xmldom records.
+ * Elements extend to their matching end tag; other nodes span their serialized text.
+ */
+function locateNode(content: string, node: LocatedNode, text: string): null | [number, number] {
+  if (node.lineNumber === undefined || node.columnNumber === undefined) return null
+
+  let start = 0
+  for (let line = 1; line < node.lineNumber; line++) {
+    start = content.indexOf('\n', start) + 1
+  }
+
+  start += node.columnNumber - 1
+
+  if (node.nodeType !== ELEMENT_NODE) {
+    return [start, Math.min(start + text.length, content.length)]
+  }
+
+  // Walk the tags from the start tag, tracking nesting of same-named elements
+  const tags = /<(\/?)([^\s/>]+)[^>]*?(\/?)>/g
+  tags.lastIndex = start
+  let depth = 0
+  for (let tag = tags.exec(content); tag; tag = tags.exec(content)) {
+    if (tag[2] !== node.nodeName) continue
+    if (tag[1]) depth--
+    else if (!tag[3]) depth++
+
+    if (depth <= 0) return [start, tag.index + tag[0].length]
+  }
+
+  return [start, content.length]
+}
+
 /**
  * XPath filter for extracting nodes from XML/HTML content.
  */
@@ -60,8 +127,8 @@ export const xpathFilter: FilterApplier<XPathFilterConfig> = {
   async apply(versions: FileVersions, config: XPathFilterConfig): Promise<FilterResult | null> {
     const {expression, namespaces} = config
 
-    const extractNodes = (content: null | string): string => {
-      if (!content) return ''
+    const extractNodes = (content: null | string): Omit<ExtractedContent, 'context'> => {
+      if (!content) return {text: ''}
 
       try {
         const doc = new DOMParser().parseFromString(content, 'text/xml')
@@ -69,28 +136,32 @@ export const xpathFilter: FilterApplier<XPathFilterConfig> = {
         const nodes = select(expression, doc)
 
         if (!nodes || (Array.isArray(nodes) && nodes.length === 0)) {
-          return ''
+          return {text: ''}
         }
 
         // Handle different result types
         if (typeof nodes === 'string' || typeof nodes === 'number' || typeof nodes === 'boolean') {
-          return String(nodes)
+          return {text: String(nodes)}
         }
 
         const serializer = new XMLSerializer()
-        return (nodes as {nodeType?: number}[])
-          .map((node) => {
-            if ('nodeType' in node) {
-              // Element or text node
-              return serializer.serializeToString(node as unknown as globalThis.Node)
-            }
-
-            return String(node)
-          })
-          .join('\n')
+        const locate = createRangeLocator(content)
+        const matches: SourceMatch[] = []
+        const texts = (nodes as LocatedNode[]).map((node) => {
+          // Element or text node
+          const isNode = 'nodeType' in node
+          const text = isNode ? serializer.serializeToString(node as unknown as globalThis.Node) : String(node)
+
+          const offsets = locateNode(content, node, text)
+          if (offsets) matches.push({range: locate(...offsets), text})
+
+          return text
+        })
+
+        return {matches, text: texts.join('\n')}
       } catch {
         // If parsing fails, return empty
-        return ''
+        return {text: ''}
       }
     }
 
@@ -99,9 +170,25 @@ export const xpathFilter: FilterApplier<XPathFilterConfig> = {
       return null
     }
 
-    const leftArtifact = extractNodes(versions.oldContent)
-    const rightArtifact = extractNodes(versions.newContent)
+    const left = extractNodes(versions.oldContent)
+    const right = extractNodes(versions.newContent)
+
+    const result = await createFilterResult(left.text, right.text)
+    if (result) {
+      if (left.matches) result.left.matches = left.matches
+      if (right.matches) result.right.matches = right.matches
+    }
+
+    return result
+  },
+
+  async validate(config: XPathFilterConfig): Promise<void> {
+    const {expression, namespaces} = config
 
-    return createFilterResult(leftArtifact, rightArtifact)
+    // Evaluating against a trivial document catches syntax errors, unknown functions
+    // and undeclared namespace prefixes, all of which apply() would silently swallow
+    const doc = new DOMParser().parseFromString('<root/>', 'text/xml')
+    const select = namespaces ? xpath.useNamespaces(namespaces) : xpath.select
+    select(expression, doc)
   },
 }
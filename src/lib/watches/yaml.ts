@@ -0,0 +1,111 @@
+import {parseAllDocuments} from 'yaml'
+
+import type {FileVersions} from '../diff/parser.js'
+import type {FilterApplier, FilterResult} from './types.js'
+
+import {compileJq, runJq} from './jq.js'
+import {createYamlPathLocator, setQueryMatches} from './paths.js'
+import {createFilterResult} from './utils.js'
+
+/**
+ * Configuration for the yaml filter.
+ * Parses YAML content and applies a jq query to it.
+ * Output is normalized JSON, so key order, comments and quoting style changes don't produce a diff.
+ *
+ * @example
+ * ```yaml
+ * # Container images in Kubernetes manifests
+ * filters:
+ *   - type: yaml
+ *     query: "select(.kind == \"Deployment\") | .spec.template.spec.containers[].image"
+ * ```
+ *
+ * @example
+ * ```yaml
+ * # Actions used by a GitHub workflow
+ * filters:
+ *   - type: yaml
+ *     query: "[.jobs[].steps[]?.uses | select(. != null)]"
+ * ```
+ */
+export interface YamlFilterConfig {
+  /**
+   * The jq query expression to apply to the parsed YAML.
+   * Multi-document files are queried one document at a time, like a stream of JSON values in jq.
+   * See https://jqlang.github.io/jq/manual/ for syntax reference.
+   *
+   * @example ".version"
+   * @example ".spec.template.spec.containers[].image"
+   * @example "select(.kind == \"Service\") | .spec.ports"
+   */
+  query: string
+
+  /**
+   * Discriminant tag identifying this as a yaml filter.
+   */
+  type: 'yaml'
+}
+
+/**
+ * Parse YAML content into a stream of JSON values, one per non-empty document.
+ *
+ * @throws Error if the YAML cannot be parsed
+ */
+export function yamlToJsonStream(content: string): string {
+  const documents = parseAllDocuments(content)
+
+  const values: string[] = []
+  for (const doc of documents) {
+    if (doc.errors.length > 0) {
+      throw new Error(`Invalid YAML: ${doc.errors[0].message}`)
+    }
+
+    // Skip empty documents, e.g. after a trailing `---`
+    if (doc.contents !== null) {
+      values.push(JSON.stringify(doc.toJS()))
+    }
+  }
+
+  return values.join('\n')
+}
+
+/**
+ * YAML filter for processing YAML content with jq queries.
+ */
+export const yamlFilter: FilterApplier<YamlFilterConfig> = {
+  async apply(versions: FileVersions, config: YamlFilterConfig): Promise<FilterResult | null> {
+    // If both are null, nothing to filter
+    if (versions.oldContent === null && versions.newContent === null) {
+      return null
+    }
+
+    // Parsed once per version, for both the artifacts and locating their matches
+    const streams = new Map<null | string, string>()
+    for (const content of [versions.oldContent, versions.newContent]) {
+      if (!streams.has(content)) streams.set(content, content ? yamlToJsonStream(content) : '')
+    }
+
+    const query = async (content: null | string) => {
+      const stream = streams.get(content)
+      return stream ? runJq(stream, config.query, {sortKeys: true}) : ''
+    }
+
+    const leftArtifact = await query(versions.oldContent)
+    const rightArtifact = await query(versions.newContent)
+
+    const result = await createFilterResult(leftArtifact, rightArtifact, false)
+    if (result) {
+      const toQueried = (content: string) => ({
+        locate: createYamlPathLocator(content),
+        stream: streams.get(content)!,
+      })
+      await setQueryMatches(result, versions, config.query, toQueried, {sortKeys: true})
+    }
+
+    return result
+  },
+
+  async validate(config: YamlFilterConfig): Promise<void> {
+    await compileJq(config.query)
+  },
+}
@@ -7,6 +7,9 @@ import {dirname, join, resolve} from 'node:path'
 import {fileURLToPath} from 'node:url'
 import {promisify} from 'node:util'
 
+import {getReportFingerprint} from '../../src/lib/reports/index.js'
+import {type LocalServer, startLocalServer} from '../helpers/http-server.js'
+
 const __dirname = dirname(fileURLToPath(import.meta.url))
 const execFileAsync = promisify(execFile)
 
@@ -154,6 +157,213 @@ describe('diff command', () => {
       expect(stdout).to.not.contain('Error')
     })
 
+    it('writes a SARIF log with --format sarif', async () => {
+      await writeFile(
+        join(tempDir, 'distill.yml'),
+        `concerns:
+  greetings:
+    signals:
+      - id: world
+        watch:
+          include: '*.txt'
+          type: regex
+          pattern: 'world'
+        report:
+          type: sarif
+          template: 'Greeting changed in {{filePath}}'
+`,
+      )
+      await writeFile(join(tempDir, 'test.txt'), 'hello\nworld')
+      await execFileAsync('git', ['add', '.'], {cwd: tempDir})
+      await execFileAsync('git', ['commit', '-m', 'update'], {cwd: tempDir})
+
+      const {stdout} = await runCommand(`diff HEAD~1 HEAD --format sarif --repo ${tempDir}`)
+      const log = JSON.parse(stdout)
+
+      expect(log.version).to.equal('2.1.0')
+      expect(log.runs[0].tool.driver.rules).to.deep.equal([
+        {id: 'greetings', shortDescription: {text: 'Changes relevant to the greetings concern'}},
+      ])
+      expect(log.runs[0].results).to.have.length(1)
+      expect(log.runs[0].results[0]).to.deep.include({
+        message: {text: 'Greeting changed in test.txt'},
+        ruleId: 'greetings',
+      })
+      expect(log.runs[0].results[0].locations[0].physicalLocation).to.deep.equal({
+        artifactLocation: {uri: 'test.txt'},
+        region: {endColumn: 6, endLine: 2, startColumn: 1, startLine: 2},
+      })
+    })
+
+    describe('--fail-on', () => {
+      beforeEach(async () => {
+        await writeFile(
+          join(tempDir, 'distill.yml'),
+          `concerns:
+  greetings:
+    severity: warning
+    signals:
+      - watch:
+          include: '*.txt'
+          type: regex
+          pattern: 'world'
+        report:
+          type: handlebars
+          template: 'Greeting changed in {{filePath}}'
+`,
+        )
+        await writeFile(join(tempDir, 'test.txt'), 'hello\nworld')
+        await execFileAsync('git', ['add', '.'], {cwd: tempDir})
+        await execFileAsync('git', ['commit', '-m', 'update'], {cwd: tempDir})
+      })
+
+      afterEach(() => {
+        process.exitCode = undefined
+      })
+
+      it('sets a failing exit status when a report meets the threshold', async () => {
+        const {stderr, stdout} = await runCommand(`diff HEAD~1 HEAD --fail-on warning --repo ${tempDir}`)
+
+        expect(stdout).to.contain('Greeting changed in test.txt')
+        expect(stderr).to.contain('1 of 1 reports have severity warning or higher')
+        expect(process.exitCode).to.equal(1)
+      })
+
+      it('keeps a passing exit status below the threshold', async () => {
+        const {stdout} = await runCommand(`diff HEAD~1 HEAD --fail-on error --json --repo ${tempDir}`)
+
+        expect(JSON.parse(stdout).reports[0]).to.have.property('severity', 'warning')
+        expect(process.exitCode).to.not.equal(1)
+      })
+    })
+
+    it('reports the other signals and fails when a signal fails on a file', async () => {
+      await writeFile(
+        join(tempDir, 'distill.yml'),
+        `concerns:
+  deps:
+    signals:
+      - id: runtime-dependencies
+        watch:
+          include: '*.json'
+          type: jq
+          query: '.dependencies'
+        report:
+          type: handlebars
+          template: 'Dependencies changed'
+      - watch:
+          include: '*.txt'
+          type: regex
+          pattern: 'world'
+        report:
+          type: handlebars
+          template: 'Greeting changed in {{filePath}}'
+`,
+      )
+      await writeFile(join(tempDir, 'package.json'), '{"dependencies": ')
+      await writeFile(join(tempDir, 'test.txt'), 'hello\nworld')
+      await execFileAsync('git', ['add', '.'], {cwd: tempDir})
+      await execFileAsync('git', ['commit', '-m', 'update'], {cwd: tempDir})
+
+      try {
+        const {stderr, stdout} = await runCommand(`diff HEAD~1 HEAD --repo ${tempDir}`)
+
+        expect(stdout).to.contain('Greeting changed in test.txt')
+        expect(stderr).to.contain('1 signal failed')
+        expect(stderr).to.contain('Signal "runtime-dependencies" of concern "deps" failed on package.json (jq watch)')
+        expect(process.exitCode).to.equal(1)
+      } finally {
+        process.exitCode = undefined
+      }
+    })
+
+    describe('notifications', () => {
+      let server: LocalServer
+
+      beforeEach(async () => {
+        server = await startLocalServer((_request, response) => {
+          response.writeHead(500).end('receiver down')
+        })
+        await writeFile(
+          join(tempDir, 'distill.yml'),
+          `notifiers:
+  webhook:
+    retries: 0
+concerns:
+  greetings:
+    signals:
+      - watch:
+          include: '*.txt'
+          type: regex
+          pattern: 'world'
+        report:
+          type: handlebars
+          template: 'Greeting changed in {{filePath}}'
+        notify:
+          webhook: '${server.url}/hook'
+`,
+        )
+        await writeFile(join(tempDir, 'test.txt'), 'hello\nworld')
+        await execFileAsync('git', ['add', '.'], {cwd: tempDir})
+        await execFileAsync('git', ['commit', '-m', 'update'], {cwd: tempDir})
+      })
+
+      afterEach(async () => {
+        delete process.env.DISTILL_NOTIFY
+        await server.close()
+      })
+
+      it('does not notify anyone without --notify', async () => {
+        const {stderr, stdout} = await runCommand(`diff HEAD~1 HEAD --repo ${tempDir}`)
+
+        expect(stdout).to.contain('Greeting changed in test.txt')
+        expect(stderr).to.contain('Skipped 1 notification; pass --notify to deliver them')
+        expect(server.requests).to.be.empty
+      })
+
+      it('lets --no-notify override DISTILL_NOTIFY', async () => {
+        process.env.DISTILL_NOTIFY = 'true'
+        await runCommand(`diff HEAD~1 HEAD --no-notify --repo ${tempDir}`)
+
+        expect(server.requests).to.be.empty
+      })
+
+      it('names the repository after its GitHub remote in webhook payloads', async () => {
+        await execFileAsync('git', ['remote', 'add', 'origin', 'git@github.com:owner/repo.git'], {cwd: tempDir})
+        await runCommand(`diff HEAD~1 HEAD --notify --repo ${tempDir}`)
+
+        expect(server.requests).to.have.length(1)
+        expect(JSON.parse(server.requests[0].body)).to.include({repository: 'owner/repo'})
+      })
+
+      it('summarizes notifications that could not be delivered', async () => {
+        const {stderr, stdout} = await runCommand(`diff HEAD~1 HEAD --notify --repo ${tempDir}`)
+
+        expect(stdout).to.contain('Greeting changed in test.txt')
+        expect(server.requests).to.have.length(1)
+        expect(stderr).to.contain('1 of 1 notifications could not be delivered')
+        expect(stderr).to.contain('receiver down')
+      })
+
+      it('prints the delivery plan with --notify-dry-run', async () => {
+        const {stderr, stdout} = await runCommand(`diff HEAD~1 HEAD --notify --notify-dry-run --json --repo ${tempDir}`)
+
+        const {reports} = JSON.parse(stdout)
+        expect(reports).to.have.length(1)
+        expect(reports[0].id).to.equal(getReportFingerprint(reports[0]))
+        expect(JSON.parse(stderr)).to.deep.equal([
+          {channel: 'webhook', reports: [reports[0].id], target: `${server.url}/hook`},
+        ])
+        expect(server.requests).to.be.empty
+      })
+    })
+
+    it('writes an empty SARIF log when there are no changes', async () => {
+      const {stdout} = await runCommand(`diff HEAD HEAD --format sarif --repo ${tempDir}`)
+      const log = JSON.parse(stdout)
+      expect(log.runs[0].results).to.be.empty
+    })
+
     it('provides user-friendly error for invalid refs', async () => {
       const {error} = await runCommand(`diff nonexistent-ref HEAD --repo ${tempDir}`)
       expect(error).to.exist
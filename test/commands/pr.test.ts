@@ -68,6 +68,304 @@ index 0000000..1111111 100644
     scope.done()
   })
 
+  it('writes a SARIF log with --format sarif', async () => {
+    const scope = nock('https://api.github.com')
+      .get('/repos/owner/repo/pulls/123')
+      .reply(200, {
+        base: {sha: 'base-sha'},
+        head: {sha: 'head-sha'},
+      })
+      .get('/repos/owner/repo/pulls/123')
+      .matchHeader('accept', 'application/vnd.github.v3.diff')
+      .reply(
+        200,
+        `diff --git a/package.json b/package.json
+index 0000000..1111111 100644
+--- a/package.json
++++ b/package.json
+@@ -1,1 +1,1 @@
+-{"dependencies": {}}
++{"dependencies": {"foo": "1.0.0"}}
+`,
+      )
+      .get('/repos/owner/repo/contents/package.json?ref=base-sha')
+      .reply(200, {
+        content: Buffer.from('{"dependencies": {}}').toString('base64'),
+        encoding: 'base64',
+      })
+      .get('/repos/owner/repo/contents/package.json?ref=head-sha')
+      .reply(200, {
+        content: Buffer.from('{"dependencies": {"foo": "1.0.0"}}').toString('base64'),
+        encoding: 'base64',
+      })
+
+    const configPath = resolve('test/fixtures/stakeholders-config.yml')
+    const {stdout} = await runCommand(`pr ${prNumber} --repo ${repo} --config ${configPath} --format sarif`)
+    const log = JSON.parse(stdout)
+
+    expect(log.runs[0].tool.driver.rules[0]).to.deep.include({id: 'dependencies'})
+    expect(log.runs[0].results).to.have.length(1)
+    expect(log.runs[0].results[0]).to.deep.include({
+      locations: [{physicalLocation: {artifactLocation: {uri: 'package.json'}}}],
+      level: 'warning',
+      message: {text: 'Dependencies changed in package.json'},
+      ruleId: 'dependencies',
+    })
+    scope.done()
+  })
+
+  it('contacts stakeholders with --contact-stakeholders', async () => {
+    const scope = nock('https://api.github.com')
+      .get('/repos/owner/repo/pulls/123')
+      .reply(200, {
+        base: {sha: 'base-sha'},
+        head: {sha: 'head-sha'},
+        user: {login: 'author'},
+      })
+      .get('/repos/owner/repo/pulls/123')
+      .matchHeader('accept', 'application/vnd.github.v3.diff')
+      .reply(
+        200,
+        `diff --git a/package.json b/package.json
+index 0000000..1111111 100644
+--- a/package.json
++++ b/package.json
+@@ -1,1 +1,1 @@
+-{"dependencies": {}}
++{"dependencies": {"foo": "1.0.0"}}
+`,
+      )
+      .get('/repos/owner/repo/contents/package.json?ref=base-sha')
+      .reply(200, {
+        content: Buffer.from('{"dependencies": {}}').toString('base64'),
+        encoding: 'base64',
+      })
+      .get('/repos/owner/repo/contents/package.json?ref=head-sha')
+      .reply(200, {
+        content: Buffer.from('{"dependencies": {"foo": "1.0.0"}}').toString('base64'),
+        encoding: 'base64',
+      })
+      // eslint-disable-next-line camelcase
+      .post('/repos/owner/repo/pulls/123/requested_reviewers', {reviewers: [], team_reviewers: ['platform']})
+      .reply(201, {})
+      .get('/repos/owner/repo/issues/123/comments')
+      .query(true)
+      .reply(200, [])
+      .post('/repos/owner/repo/issues/123/comments', (body) => body.body.includes('- @alice (Alice'))
+      .reply(201, {})
+
+    const configPath = resolve('test/fixtures/stakeholders-config.yml')
+    const {stdout} = await runCommand(`pr ${prNumber} --repo ${repo} --config ${configPath} --contact-stakeholders`)
+
+    expect(stdout).to.contain('Dependencies changed in package.json')
+    scope.done()
+  })
+
+  describe('notify.github', () => {
+    function mockPullRequest(pr: Record<string, unknown>) {
+      return nock('https://api.github.com')
+        .get('/repos/owner/repo/pulls/123')
+        .reply(200, {base: {sha: 'base-sha'}, head: {sha: 'head-sha'}, ...pr})
+        .get('/repos/owner/repo/pulls/123')
+        .matchHeader('accept', 'application/vnd.github.v3.diff')
+        .reply(
+          200,
+          `diff --git a/package.json b/package.json
+index 0000000..1111111 100644
+--- a/package.json
++++ b/package.json
+@@ -1,1 +1,1 @@
+-{"dependencies": {}}
++{"dependencies": {"foo": "1.0.0"}}
+`,
+        )
+        // Each of the three signals fetches the file
+        .get('/repos/owner/repo/contents/package.json?ref=base-sha')
+        .times(3)
+        .reply(200, {content: Buffer.from('{"dependencies": {}}').toString('base64'), encoding: 'base64'})
+        .get('/repos/owner/repo/contents/package.json?ref=head-sha')
+        .times(3)
+        .reply(200, {
+          content: Buffer.from('{"dependencies": {"foo": "1.0.0"}}').toString('base64'),
+          encoding: 'base64',
+        })
+    }
+
+    it('requests reviews from notify targets with --notify, skipping the author and requested reviewers', async () => {
+      const scope = mockPullRequest({
+        // eslint-disable-next-line camelcase
+        requested_reviewers: [{login: 'carol'}],
+        // eslint-disable-next-line camelcase
+        requested_teams: [{slug: 'platform'}],
+        user: {login: 'bob'},
+      })
+        .get('/repos/owner/repo/issues/123/comments')
+        .query(true)
+        .reply(200, [])
+        .post('/repos/owner/repo/issues/123/comments', (body) => body.body.includes('- @alice (Alice)'))
+        .reply(201, {})
+
+      const configPath = resolve('test/fixtures/notify-config.yml')
+      await runCommand(`pr ${prNumber} --repo ${repo} --config ${configPath} --notify`)
+
+      // bob is the author and the platform team is already requested, so only alice is contacted
+      scope.done()
+    })
+
+    it('does not contact notify targets without --notify', async () => {
+      const scope = mockPullRequest({user: {login: 'author'}})
+
+      const configPath = resolve('test/fixtures/notify-config.yml')
+      const {stderr} = await runCommand(`pr ${prNumber} --repo ${repo} --config ${configPath}`)
+
+      expect(stderr).to.contain('Skipped 3 notifications; pass --notify to deliver them')
+      scope.done()
+    })
+
+    it('lists notify targets in the delivery plan with --notify-dry-run', async () => {
+      const scope = mockPullRequest({user: {login: 'author'}})
+
+      const configPath = resolve('test/fixtures/notify-config.yml')
+      const {stderr} = await runCommand(`pr ${prNumber} --repo ${repo} --config ${configPath} --notify-dry-run --json`)
+      const plan: Array<{channel: string; target: string}> = JSON.parse(stderr)
+
+      expect(plan.map(({channel, target}) => `${channel} ${target}`)).to.deep.equal([
+        'github @bob',
+        'github @Alice',
+        'github owner/platform',
+      ])
+      scope.done()
+    })
+
+    it('prints the planned stakeholder contacts with --notify-dry-run', async () => {
+      const scope = mockPullRequest({user: {login: 'author'}})
+
+      const configPath = resolve('test/fixtures/notify-config.yml')
+      const {stdout} = await runCommand(
+        `pr ${prNumber} --repo ${repo} --config ${configPath} --contact-stakeholders --notify-dry-run`,
+      )
+
+      expect(stdout).to.contain('Would mention @alice in a comment')
+      scope.done()
+    })
+  })
+
+  it('publishes a check run with --check-run', async () => {
+    const scope = nock('https://api.github.com')
+      .get('/repos/owner/repo/pulls/123')
+      .reply(200, {
+        base: {sha: 'base-sha'},
+        head: {sha: 'head-sha'},
+      })
+      .get('/repos/owner/repo/pulls/123')
+      .matchHeader('accept', 'application/vnd.github.v3.diff')
+      .reply(
+        200,
+        `diff --git a/package.json b/package.json
+index 0000000..1111111 100644
+--- a/package.json
++++ b/package.json
+@@ -1,1 +1,1 @@
+-{"dependencies": {}}
++{"dependencies": {"foo": "1.0.0"}}
+`,
+      )
+      .get('/repos/owner/repo/contents/package.json?ref=base-sha')
+      .reply(200, {
+        content: Buffer.from('{"dependencies": {}}').toString('base64'),
+        encoding: 'base64',
+      })
+      .get('/repos/owner/repo/contents/package.json?ref=head-sha')
+      .reply(200, {
+        content: Buffer.from('{"dependencies": {"foo": "1.0.0"}}').toString('base64'),
+        encoding: 'base64',
+      })
+      .post(
+        '/repos/owner/repo/check-runs',
+        (body) =>
+          body.conclusion === 'failure' &&
+          body.output.summary.includes('| dependencies | 1 | `package.json` |') &&
+          body.output.annotations[0].path === 'package.json',
+      )
+      .reply(201, {id: 1})
+
+    const configPath = resolve('test/fixtures/stakeholders-config.yml')
+    await runCommand(`pr ${prNumber} --repo ${repo} --config ${configPath} --check-run --check-conclusion failure`)
+
+    scope.done()
+  })
+
+  it('posts reports as a sticky comment with --comment', async () => {
+    const scope = nock('https://api.github.com')
+      .get('/repos/owner/repo/pulls/123')
+      .reply(200, {
+        base: {sha: 'base-sha'},
+        head: {sha: 'head-sha'},
+      })
+      .get('/repos/owner/repo/pulls/123')
+      .matchHeader('accept', 'application/vnd.github.v3.diff')
+      .reply(
+        200,
+        `diff --git a/package.json b/package.json
+index 0000000..1111111 100644
+--- a/package.json
++++ b/package.json
+@@ -1,1 +1,1 @@
+-{"dependencies": {}}
++{"dependencies": {"foo": "1.0.0"}}
+`,
+      )
+      .get('/repos/owner/repo/contents/package.json?ref=base-sha')
+      .reply(200, {
+        content: Buffer.from('{"dependencies": {}}').toString('base64'),
+        encoding: 'base64',
+      })
+      .get('/repos/owner/repo/contents/package.json?ref=head-sha')
+      .reply(200, {
+        content: Buffer.from('{"dependencies": {"foo": "1.0.0"}}').toString('base64'),
+        encoding: 'base64',
+      })
+      .get('/repos/owner/repo/issues/123/comments')
+      .query(true)
+      // eslint-disable-next-line camelcase
+      .reply(200, [{body: '<!-- distill:report -->\nold', id: 99, node_id: 'IC_99'}])
+      .patch(
+        '/repos/owner/repo/issues/comments/99',
+        (body) => body.body.includes('### dependencies') && body.body.includes('Dependencies changed in package.json'),
+      )
+      .reply(200, {id: 99})
+
+    const configPath = resolve('test/fixtures/stakeholders-config.yml')
+    const {stdout} = await runCommand(`pr ${prNumber} --repo ${repo} --config ${configPath} --comment`)
+
+    expect(stdout).to.contain('Dependencies changed in package.json')
+    scope.done()
+  })
+
+  it('deletes the sticky comment when nothing fires', async () => {
+    const scope = nock('https://api.github.com')
+      .get('/repos/owner/repo/pulls/123')
+      .reply(200, {
+        base: {sha: 'base-sha'},
+        head: {sha: 'head-sha'},
+      })
+      .get('/repos/owner/repo/pulls/123')
+      .matchHeader('accept', 'application/vnd.github.v3.diff')
+      .reply(200, '')
+      .get('/repos/owner/repo/issues/123/comments')
+      .query(true)
+      // eslint-disable-next-line camelcase
+      .reply(200, [{body: '<!-- distill:report -->\nold', id: 99, node_id: 'IC_99'}])
+      .delete('/repos/owner/repo/issues/comments/99')
+      .reply(204)
+
+    const configPath = resolve('test/fixtures/test-config.yml')
+    const {stdout} = await runCommand(`pr ${prNumber} --repo ${repo} --config ${configPath} --comment`)
+
+    expect(stdout).to.contain('No changes found in PR #123')
+    scope.done()
+  })
+
   describe('URL parsing', () => {
     it('accepts full GitHub PR URL', async () => {
       const scope = nock('https://api.github.com')
@@ -0,0 +1,212 @@
+import {runCommand} from '@oclif/test'
+import {expect} from 'chai'
+import {mkdtemp, rm, writeFile} from 'node:fs/promises'
+import {tmpdir} from 'node:os'
+import {dirname, join, resolve} from 'node:path'
+import {fileURLToPath} from 'node:url'
+
+const __dirname = dirname(fileURLToPath(import.meta.url))
+
+describe('validate', () => {
+  let tempDir: string
+
+  beforeEach(async () => {
+    tempDir = await mkdtemp(join(tmpdir(), 'distill-validate-test-'))
+  })
+
+  afterEach(async () => {
+    process.exitCode = undefined
+    await rm(tempDir, {force: true, recursive: true})
+  })
+
+  it('reports a valid configuration', async () => {
+    const configPath = resolve(__dirname, '../fixtures/test-config.yml')
+    const {stdout} = await runCommand(`validate --config ${configPath}`)
+
+    expect(stdout).to.contain('is valid')
+    expect(process.exitCode).to.not.equal(1)
+  })
+
+  it('reports structural problems with their location and exits non-zero', async () => {
+    const configPath = join(tempDir, 'distill.yml')
+    await writeFile(
+      configPath,
+      `concerns:
+  security:
+    signals:
+      - watch:
+          include: '*.ts'
+          type: regex
+        report:
+          type: handlebars
+          template: 'x'
+`,
+    )
+
+    const {stdout} = await runCommand(`validate --config ${configPath}`)
+
+    expect(stdout).to.contain('Invalid configuration')
+    expect(stdout).to.contain('5:11 concerns.security.signals[0].watch: Missing required property "pattern"')
+    expect(process.exitCode).to.equal(1)
+  })
+
+  it('compiles regexes and templates', async () => {
+    const configPath = join(tempDir, 'distill.yml')
+    await writeFile(
+      configPath,
+      `defined:
+  reports:
+    broken:
+      type: handlebars
+      template: '{{#if filePath}}unclosed'
+concerns:
+  security:
+    signals:
+      - watch:
+          include: '*.ts'
+          type: regex
+          pattern: '(unclosed'
+        report:
+          use: '#defined/reports/broken'
+`,
+    )
+
+    const {stdout} = await runCommand(`validate --config ${configPath} --json`)
+    const result = JSON.parse(stdout)
+
+    expect(result.valid).to.equal(false)
+    expect(result.issues).to.have.length(2)
+    expect(result.issues[0]).to.deep.include({line: 5, path: 'defined.reports.broken.template'})
+    expect(result.issues[0].message).to.contain('Invalid template')
+    expect(result.issues[1]).to.deep.include({
+      concernId: 'security',
+      path: 'concerns.security.signals[0].watch',
+      signalIndex: 0,
+    })
+    expect(result.issues[1].message).to.contain('Invalid regex watch')
+    expect(process.exitCode).to.equal(1)
+  })
+
+  it('rejects scoped watches that cannot locate their matches', async () => {
+    const configPath = join(tempDir, 'distill.yml')
+    await writeFile(
+      configPath,
+      `concerns:
+  ci:
+    signals:
+      - watch:
+          include: '.github/workflows/*.yml'
+          type: yaml
+          scope: changed-lines
+          query: '[.jobs[]?.steps[]?.uses]'
+        report:
+          type: handlebars
+          template: 'Actions changed'
+      - watch:
+          include: 'pom.xml'
+          type: xpath
+          scope: changed-hunks
+          expression: 'count(//dependency)'
+        report:
+          type: handlebars
+          template: 'Dependencies changed'
+`,
+    )
+
+    const {stdout} = await runCommand(`validate --config ${configPath} --json`)
+    const result = JSON.parse(stdout)
+
+    expect(result.valid).to.equal(false)
+    expect(result.issues.map((issue: {path: string}) => issue.path)).to.deep.equal([
+      'concerns.ci.signals[0].watch.scope',
+      'concerns.ci.signals[1].watch.scope',
+    ])
+    expect(result.issues[0].message).to.contain('Cannot scope this yaml watch: Invalid path expression')
+    expect(result.issues[1].message).to.contain('Cannot scope this xpath watch: the expression evaluates to a number')
+    expect(process.exitCode).to.equal(1)
+  })
+
+  it('compiles json report fields', async () => {
+    const configPath = join(tempDir, 'distill.yml')
+    await writeFile(
+      configPath,
+      `concerns:
+  deps:
+    signals:
+      - watch:
+          include: 'package.json'
+          type: jq
+          query: '.dependencies'
+        report:
+          type: json
+          fields:
+            file: '{{filePath}}'
+            summary: '{{#each}}'
+`,
+    )
+
+    const {stdout} = await runCommand(`validate --config ${configPath} --json`)
+    const result = JSON.parse(stdout)
+
+    expect(result.issues).to.have.length(1)
+    expect(result.issues[0]).to.deep.include({line: 12, path: 'concerns.deps.signals[0].report.fields.summary'})
+    expect(result.issues[0].message).to.contain('Invalid template')
+  })
+
+  it('compiles notify target templates', async () => {
+    const configPath = join(tempDir, 'distill.yml')
+    await writeFile(
+      configPath,
+      `concerns:
+  services:
+    signals:
+      - watch:
+          include: 'services/**'
+          type: regex
+          pattern: 'owner'
+        report:
+          type: handlebars
+          template: 'Owner changed in {{filePath}}'
+        notify:
+          slack: '#team-{{pathSegments.[1]'
+`,
+    )
+
+    const {stdout} = await runCommand(`validate --config ${configPath} --json`)
+    const result = JSON.parse(stdout)
+
+    expect(result.issues).to.have.length(1)
+    expect(result.issues[0]).to.deep.include({line: 12, path: 'concerns.services.signals[0].notify.slack'})
+    expect(result.issues[0].message).to.contain('Invalid template')
+  })
+
+  it('compiles tree-sitter queries for each language matched by include', async () => {
+    const configPath = join(tempDir, 'distill.yml')
+    await writeFile(
+      configPath,
+      `concerns:
+  code:
+    signals:
+      - watch:
+          include: 'src/**/*.{js,py}'
+          type: tsq
+          query: '(function_declaration) @fn'
+        report:
+          type: handlebars
+          template: 'x'
+`,
+    )
+
+    const {stdout} = await runCommand(`validate --config ${configPath} --json`)
+    const result = JSON.parse(stdout)
+
+    // function_declaration exists in the JavaScript grammar but not in Python's
+    expect(result.issues).to.have.length(1)
+    expect(result.issues[0].message).to.contain('Invalid tsq watch (as .py)')
+  })
+
+  it('errors when the configuration file is missing', async () => {
+    const {error} = await runCommand(`validate --config ${join(tempDir, 'missing.yml')}`)
+    expect(error?.message).to.contain('Cannot read configuration file')
+  })
+})
@@ -55,6 +55,9 @@ export const fixtures = {
   regex: {
     config: () => loadVersions('filters/regex', 'config-v1.env', 'config-v2.env'),
   },
+  toml: {
+    cargo: () => loadVersions('filters/toml', 'Cargo-v1.toml', 'Cargo-v2.toml'),
+  },
   tsq: {
     go: () => loadVersions('filters/tsq/go', 'handlers-v1.go', 'handlers-v2.go'),
     java: () => loadVersions('filters/tsq/java', 'UserService-v1.java', 'UserService-v2.java'),
@@ -67,4 +70,7 @@ export const fixtures = {
   xpath: {
     pom: () => loadVersions('filters/xpath', 'pom-v1.xml', 'pom-v2.xml'),
   },
+  yaml: {
+    deployment: () => loadVersions('filters/yaml', 'deployment-v1.yaml', 'deployment-v2.yaml'),
+  },
 }
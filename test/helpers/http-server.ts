@@ -0,0 +1,54 @@
+import {createServer, type IncomingHttpHeaders, type ServerResponse} from 'node:http'
+import {type AddressInfo} from 'node:net'
+
+export interface ReceivedRequest {
+  body: string
+  headers: IncomingHttpHeaders
+  method?: string
+  url?: string
+}
+
+export interface LocalServer {
+  close(): Promise<void>
+  requests: ReceivedRequest[]
+  url: string
+}
+
+/**
+ * Start an HTTP server on a free local port that records each request and answers it with `respond`.
+ * Stands in for Slack, webhook receivers and other HTTP APIs in tests.
+ */
+export async function startLocalServer(
+  respond: (request: ReceivedRequest, response: ServerResponse, index: number) => void,
+): Promise<LocalServer> {
+  const requests: ReceivedRequest[] = []
+  const server = createServer((incoming, response) => {
+    const chunks: Buffer[] = []
+    incoming.on('data', (chunk: Buffer) => chunks.push(chunk))
+    incoming.on('end', () => {
+      const request = {
+        body: Buffer.concat(chunks).toString('utf8'),
+        headers: incoming.headers,
+        method: incoming.method,
+        url: incoming.url,
+      }
+      requests.push(request)
+      respond(request, response, requests.length - 1)
+    })
+  })
+
+  await new Promise<void>((resolve) => {
+    server.listen(0, '127.0.0.1', resolve)
+  })
+  const {port} = server.address() as AddressInfo
+
+  return {
+    close: () =>
+      new Promise<void>((resolve) => {
+        server.closeAllConnections()
+        server.close(() => resolve())
+      }),
+    requests,
+    url: `http://127.0.0.1:${port}`,
+  }
+}
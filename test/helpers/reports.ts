@@ -0,0 +1,38 @@
+import type {NotifyConfig} from '../../src/lib/configuration/config.js'
+import type {ReportLocation, ReportMetadata, ReportOutput} from '../../src/lib/reports/index.js'
+
+export type ReportOverrides = Partial<ReportMetadata> & {
+  content?: string
+  /** Lines of the new and old file to locate the finding on, one location per line */
+  lines?: {new?: number[]; old?: number[]}
+  notify?: NotifyConfig
+}
+
+/**
+ * Build a report as the runner produces it: a `security` finding in `src/auth.ts` whose message is its content,
+ * unless overridden. Stands in for processed reports in tests of the outputs and notifiers that consume them.
+ */
+export function buildReport({content = 'report', lines, notify, ...metadata}: ReportOverrides = {}): ReportOutput {
+  const fileName = metadata.fileName ?? 'src/auth.ts'
+  const locate = (line: number): ReportLocation => ({
+    endColumn: 12,
+    endLine: line,
+    path: fileName,
+    startColumn: 3,
+    startLine: line,
+  })
+
+  return {
+    content,
+    metadata: {
+      concernId: 'security',
+      diffText: '',
+      fileName,
+      message: content,
+      signalId: '0',
+      ...(lines ? {locations: {new: (lines.new ?? []).map(locate), old: (lines.old ?? []).map(locate)}} : {}),
+      ...metadata,
+    },
+    ...(notify ? {notify} : {}),
+  }
+}
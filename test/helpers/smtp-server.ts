@@ -0,0 +1,88 @@
+import {createServer, type Socket} from 'node:net'
+import {type AddressInfo} from 'node:net'
+
+export interface ReceivedMail {
+  data: string
+  from: string
+  to: string[]
+}
+
+export interface SmtpSink {
+  close(): Promise<void>
+  messages: ReceivedMail[]
+  port: number
+}
+
+/**
+ * Start an SMTP server on a free local port that accepts every message without TLS or auth,
+ * and records what it receives.
+ */
+export async function startSmtpSink(): Promise<SmtpSink> {
+  const messages: ReceivedMail[] = []
+  const sockets = new Set<Socket>()
+
+  const server = createServer((socket) => {
+    sockets.add(socket)
+    socket.on('close', () => sockets.delete(socket))
+
+    let buffer = ''
+    let mail: ReceivedMail = {data: '', from: '', to: []}
+    let inData = false
+
+    socket.write('220 localhost SMTP sink\r\n')
+    socket.on('data', (chunk) => {
+      buffer += chunk.toString('utf8')
+
+      while (buffer) {
+        if (inData) {
+          const end = buffer.indexOf('\r\n.\r\n')
+          if (end === -1) return
+          mail.data = buffer.slice(0, end)
+          messages.push(mail)
+          mail = {data: '', from: '', to: []}
+          buffer = buffer.slice(end + 5)
+          inData = false
+          socket.write('250 OK queued\r\n')
+          continue
+        }
+
+        const lineEnd = buffer.indexOf('\r\n')
+        if (lineEnd === -1) return
+        const line = buffer.slice(0, lineEnd)
+        buffer = buffer.slice(lineEnd + 2)
+
+        const command = line.slice(0, 4).toUpperCase()
+        if (command === 'EHLO' || command === 'HELO') {
+          socket.write('250 localhost\r\n')
+        } else if (command === 'MAIL') {
+          mail.from = line.replace(/^MAIL FROM:\s*/i, '')
+          socket.write('250 OK\r\n')
+        } else if (command === 'RCPT') {
+          mail.to.push(line.replace(/^RCPT TO:\s*/i, ''))
+          socket.write('250 OK\r\n')
+        } else if (command === 'DATA') {
+          inData = true
+          socket.write('354 End data with <CR><LF>.<CR><LF>\r\n')
+        } else if (command === 'QUIT') {
+          socket.end('221 Bye\r\n')
+        } else {
+          socket.write('250 OK\r\n')
+        }
+      }
+    })
+  })
+
+  await new Promise<void>((resolve) => {
+    server.listen(0, '127.0.0.1', resolve)
+  })
+
+  return {
+    close: () =>
+      new Promise<void>((resolve) => {
+        for (const socket of sockets) socket.destroy()
+        server.close(() => resolve())
+      }),
+    messages,
+    port: (server.address() as AddressInfo).port,
+  }
+}
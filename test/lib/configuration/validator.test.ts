@@ -0,0 +1,227 @@
+import {expect} from 'chai'
+
+import {parseConfig} from '../../../src/lib/configuration/loader.js'
+import {ConfigValidationError, validateConfigSource} from '../../../src/lib/configuration/validator.js'
+
+describe('configuration/validator', () => {
+  it('accepts a valid configuration', () => {
+    const {issues} = validateConfigSource(`
+concerns:
+  security:
+    signals:
+      - watch:
+          include: 'src/**/*.ts'
+          type: regex
+          pattern: 'secret'
+        report:
+          type: handlebars
+          template: 'Found a secret'
+`)
+
+    expect(issues).to.be.empty
+  })
+
+  it('reports a misspelled discriminant with its location', () => {
+    const {issues} = validateConfigSource(`concerns:
+  security:
+    signals:
+      - watch:
+          include: 'src/**/*.ts'
+          tpye: regex
+          pattern: 'secret'
+        report:
+          type: handlebars
+          template: 'Found a secret'
+`)
+
+    expect(issues).to.have.length(2)
+    expect(issues[0]).to.deep.include({
+      column: 11,
+      concernId: 'security',
+      line: 5,
+      path: 'concerns.security.signals[0].watch',
+      signalIndex: 0,
+    })
+    expect(issues[0].message).to.include('Missing required property "type"')
+    expect(issues[1]).to.deep.include({line: 6, path: 'concerns.security.signals[0].watch.tpye'})
+    expect(issues[1].message).to.include('Unknown property "tpye"')
+  })
+
+  it('reports an unknown watch type', () => {
+    const {issues} = validateConfigSource(`concerns:
+  security:
+    signals:
+      - watch:
+          include: '*.yml'
+          type: yamlish
+          query: '.foo'
+        report:
+          type: handlebars
+          template: 'x'
+`)
+
+    expect(issues).to.have.length(1)
+    expect(issues[0].path).to.equal('concerns.security.signals[0].watch.type')
+    expect(issues[0].message).to.include('Unknown type string "yamlish"')
+    expect(issues[0].line).to.equal(6)
+  })
+
+  it('reports an unsupported ast-grep language', () => {
+    const {issues} = validateConfigSource(`concerns:
+  scripts:
+    signals:
+      - watch:
+          include: '**/*.rb'
+          type: ast-grep
+          language: ruby
+          pattern: 'system($$$ARGS)'
+        report:
+          type: handlebars
+          template: 'x'
+`)
+
+    expect(issues).to.have.length(1)
+    expect(issues[0].path).to.equal('concerns.scripts.signals[0].watch.language')
+    expect(issues[0].message).to.include('but got string "ruby"')
+  })
+
+  it('reports what a union accepts when a value fits none of its forms', () => {
+    const {issues} = validateConfigSource(`concerns:
+  scripts:
+    signals:
+      - watch:
+          include: '**/*.ts'
+          type: ast-grep
+          language: typescript
+          rule:
+            pattern: 'eval($CODE)'
+            inside:
+              kind: function_declaration
+              stopBy: nowhere
+        report:
+          type: handlebars
+          template: 'x'
+`)
+
+    expect(issues).to.have.length(1)
+    expect(issues[0]).to.deep.include({line: 12, path: 'concerns.scripts.signals[0].watch.rule.inside.stopBy'})
+    expect(issues[0].message).to.equal('Expected "end" or "neighbor" or object but got string "nowhere"')
+  })
+
+  it('reports problems in a list of globs at the offending entry', () => {
+    const {issues} = validateConfigSource(`concerns:
+  security:
+    signals:
+      - watch:
+          include: ['src/**/*.ts', 5]
+          type: regex
+          pattern: 'secret'
+        report:
+          type: handlebars
+          template: 'x'
+`)
+
+    expect(issues).to.have.length(1)
+    expect(issues[0].path).to.equal('concerns.security.signals[0].watch.include[1]')
+    expect(issues[0].message).to.equal('Expected string but got number 5')
+  })
+
+  it('reports every problem at once', () => {
+    const {issues} = validateConfigSource(`concerns:
+  first:
+    signals:
+      - watch:
+          include: '*.json'
+          type: jq
+          query: '.a'
+  second:
+    signals:
+      - watch:
+          include: '*.json'
+          type: jq
+        report:
+          type: handlebars
+          template: 'x'
+`)
+
+    expect(issues.map((i) => i.message)).to.deep.equal([
+      'Missing required property "report"',
+      'Missing required property "query"',
+    ])
+    expect(issues.map((i) => i.concernId)).to.deep.equal(['first', 'second'])
+  })
+
+  it('resolves defined references up front', () => {
+    const {issues} = validateConfigSource(`defined:
+  watches:
+    deps:
+      include: package.json
+      type: jq
+      query: '.dependencies'
+concerns:
+  deps:
+    signals:
+      - watch:
+          use: '#defined/watches/deps'
+        report:
+          use: '#defined/reports/missing'
+      - use: '#defined/watches/deps'
+`)
+
+    expect(issues).to.have.length(2)
+    expect(issues[0]).to.deep.include({path: 'concerns.deps.signals[0].report.use', signalIndex: 0})
+    expect(issues[0].message).to.include('Report "missing" not found')
+    expect(issues[1]).to.deep.include({path: 'concerns.deps.signals[1].use', signalIndex: 1})
+    expect(issues[1].message).to.include('Expected a signal reference')
+  })
+
+  it('reports duplicate signal ids within a concern', () => {
+    const {issues} = validateConfigSource(`defined:
+  signals:
+    shared:
+      id: deps
+      watch:
+        include: package.json
+        type: jq
+        query: '.dependencies'
+      report:
+        type: handlebars
+        template: 'x'
+concerns:
+  deps:
+    signals:
+      - use: '#defined/signals/shared'
+      - id: deps
+        watch:
+          include: package.json
+          type: jq
+          query: '.devDependencies'
+        report:
+          type: handlebars
+          template: 'y'
+`)
+
+    expect(issues).to.have.length(1)
+    expect(issues[0]).to.deep.include({line: 16, path: 'concerns.deps.signals[1].id', signalIndex: 1})
+    expect(issues[0].message).to.equal('Duplicate signal id "deps" (also used by signal 0)')
+  })
+
+  it('reports YAML syntax errors with their location', () => {
+    const {issues} = validateConfigSource('concerns:\n  security: [\n')
+
+    expect(issues).to.not.be.empty
+    expect(issues[0].line).to.be.a('number')
+  })
+
+  it('throws a ConfigValidationError listing all issues from parseConfig', () => {
+    try {
+      parseConfig('concerns:\n  security:\n    signals: {}\n', 'distill.yml')
+      expect.fail('Should have thrown')
+    } catch (error) {
+      expect(error).to.be.instanceOf(ConfigValidationError)
+      expect((error as ConfigValidationError).issues).to.have.length(1)
+      expect((error as Error).message).to.include('Invalid configuration in distill.yml')
+      expect((error as Error).message).to.include('3:14 concerns.security.signals: Expected array but got object')
+    }
+  })
+})
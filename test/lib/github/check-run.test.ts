@@ -0,0 +1,111 @@
+/* eslint-disable camelcase */
+import {expect} from 'chai'
+import nock from 'nock'
+import {Octokit} from 'octokit'
+
+import {getCheckAnnotations, publishCheckRun, renderCheckSummary} from '../../../src/lib/github/check-run.js'
+import {buildReport} from '../../helpers/reports.js'
+
+const warning = {severity: 'warning'} as const
+
+describe('github/check-run', () => {
+  const repository = {owner: 'owner', repo: 'repo'}
+  let octokit: Octokit
+
+  beforeEach(() => {
+    octokit = new Octokit({auth: 'gh_token'})
+    nock.disableNetConnect()
+  })
+
+  afterEach(() => {
+    nock.cleanAll()
+    nock.enableNetConnect()
+  })
+
+  describe('getCheckAnnotations', () => {
+    it('annotates changed lines, falling back to the first line of the file', () => {
+      const [inline, fileLevel] = getCheckAnnotations([
+        buildReport({...warning, content: 'security changed', fileName: 'a.ts', lines: {new: [4]}}),
+        buildReport({...warning, concernId: 'deps', fileName: 'b.json'}),
+      ])
+
+      expect(inline).to.deep.equal({
+        annotation_level: 'warning',
+        end_column: 11,
+        end_line: 4,
+        message: 'security changed',
+        path: 'a.ts',
+        start_column: 3,
+        start_line: 4,
+        title: 'security: 0',
+      })
+      expect(fileLevel).to.deep.include({end_line: 1, path: 'b.json', start_line: 1})
+      expect(fileLevel).to.not.have.property('start_column')
+    })
+  })
+
+  describe('renderCheckSummary', () => {
+    it('tabulates signals and files per concern', () => {
+      const summary = renderCheckSummary([
+        buildReport({fileName: 'a.ts'}),
+        buildReport({fileName: 'b.ts'}),
+        buildReport({concernId: 'deps', fileName: 'package.json'}),
+      ])
+
+      expect(summary).to.contain('3 signals fired across 2 concerns.')
+      expect(summary).to.contain('| security | 2 | `a.ts`, `b.ts` |')
+      expect(summary).to.contain('| deps | 1 | `package.json` |')
+    })
+  })
+
+  describe('publishCheckRun', () => {
+    it('batches annotations 50 per request', async () => {
+      const reports = Array.from({length: 120}, (_, index) => buildReport({...warning, lines: {new: [index + 1]}}))
+      const batchSizes: number[] = []
+      const scope = nock('https://api.github.com')
+        .post('/repos/owner/repo/check-runs', (body) => {
+          batchSizes.push(body.output.annotations.length)
+          return body.head_sha === 'head-sha' && body.conclusion === 'failure' && body.status === 'completed'
+        })
+        .reply(201, {id: 9})
+        .patch('/repos/owner/repo/check-runs/9', (body) => {
+          batchSizes.push(body.output.annotations.length)
+          return true
+        })
+        .twice()
+        .reply(200, {id: 9})
+
+      const outcome = await publishCheckRun(octokit, repository, 'head-sha', reports, 'failure')
+
+      expect(batchSizes).to.deep.equal([50, 50, 20])
+      expect(outcome).to.deep.equal({annotations: 120, conclusion: 'failure', id: 9})
+      scope.done()
+    })
+
+    it('is neutral when only info signals fired', async () => {
+      const scope = nock('https://api.github.com')
+        .post('/repos/owner/repo/check-runs', (body) => body.conclusion === 'neutral')
+        .reply(201, {id: 4})
+
+      const reports = [buildReport({concernId: 'docs', fileName: 'README.md', lines: {new: [1]}, severity: 'info'})]
+      expect(await publishCheckRun(octokit, repository, 'head-sha', reports, 'failure')).to.deep.include({
+        conclusion: 'neutral',
+      })
+      scope.done()
+    })
+
+    it('succeeds when nothing fired', async () => {
+      const scope = nock('https://api.github.com')
+        .post(
+          '/repos/owner/repo/check-runs',
+          (body) => body.conclusion === 'success' && body.output.title === 'No signals fired',
+        )
+        .reply(201, {id: 3})
+
+      expect(await publishCheckRun(octokit, repository, 'head-sha', [], 'failure')).to.deep.include({
+        conclusion: 'success',
+      })
+      scope.done()
+    })
+  })
+})
@@ -0,0 +1,138 @@
+/* eslint-disable camelcase */
+import {expect} from 'chai'
+import nock from 'nock'
+import {Octokit} from 'octokit'
+
+import {
+  clearReportComment,
+  COMMENT_MARKER,
+  renderReportComment,
+  upsertReportComment,
+} from '../../../src/lib/github/comment.js'
+import {buildReport} from '../../helpers/reports.js'
+
+describe('github/comment', () => {
+  const pr = {number: 7, owner: 'owner', repo: 'repo'}
+  let octokit: Octokit
+
+  beforeEach(() => {
+    octokit = new Octokit({auth: 'gh_token'})
+    nock.disableNetConnect()
+  })
+
+  afterEach(() => {
+    nock.cleanAll()
+    nock.enableNetConnect()
+  })
+
+  describe('renderReportComment', () => {
+    it('groups reports by concern under the hidden marker', () => {
+      const security = buildReport({
+        content: 'Secret added\n',
+        stakeholders: [{contactMethod: 'github-comment-mention', name: 'Security Team'}],
+      })
+      const deps = buildReport({concernId: 'deps', content: 'Lockfile changed'})
+
+      const body = renderReportComment([security, deps, buildReport({content: 'Key'})])
+
+      expect(body.startsWith(COMMENT_MARKER)).to.equal(true)
+      expect(body).to.contain('3 signals fired across 2 concerns.')
+      expect(body).to.contain('### security\n\n_Stakeholders: Security Team_\n\nSecret added\n\nKey')
+      expect(body.indexOf('### security')).to.be.lessThan(body.indexOf('### deps'))
+    })
+
+    it('truncates bodies that exceed the GitHub limit', () => {
+      const body = renderReportComment([buildReport({content: 'x'.repeat(70_000)})])
+      expect(body.length).to.equal(65_536)
+      expect(body).to.match(/This comment was truncated/)
+    })
+  })
+
+  describe('upsertReportComment', () => {
+    it('creates a comment when none exists', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body: 'LGTM', id: 1, node_id: 'IC_1'}])
+        .post('/repos/owner/repo/issues/7/comments', {body: `${COMMENT_MARKER}\nnew`})
+        .reply(201, {id: 2})
+
+      expect(await upsertReportComment(octokit, pr, `${COMMENT_MARKER}\nnew`)).to.equal('created')
+      scope.done()
+    })
+
+    it('updates the marked comment in place', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body: `${COMMENT_MARKER}\nold`, id: 5, node_id: 'IC_5'}])
+        .patch('/repos/owner/repo/issues/comments/5', {body: `${COMMENT_MARKER}\nnew`})
+        .reply(200, {id: 5})
+
+      expect(await upsertReportComment(octokit, pr, `${COMMENT_MARKER}\nnew`)).to.equal('updated')
+      scope.done()
+    })
+
+    it('leaves an identical comment alone', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body: `${COMMENT_MARKER}\nsame`, id: 5, node_id: 'IC_5'}])
+
+      expect(await upsertReportComment(octokit, pr, `${COMMENT_MARKER}\nsame`)).to.equal('unchanged')
+      scope.done()
+    })
+
+    it('expands a collapsed comment when signals fire again', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body: `${COMMENT_MARKER}\n<!-- distill:resolved -->\nnothing`, id: 5, node_id: 'IC_5'}])
+        .patch('/repos/owner/repo/issues/comments/5')
+        .reply(200, {id: 5})
+        .post('/graphql', (body) => body.query.includes('unminimizeComment') && body.variables.id === 'IC_5')
+        .reply(200, {data: {unminimizeComment: {clientMutationId: null}}})
+
+      expect(await upsertReportComment(octokit, pr, `${COMMENT_MARKER}\nnew`)).to.equal('updated')
+      scope.done()
+    })
+  })
+
+  describe('clearReportComment', () => {
+    it('deletes the marked comment', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body: `${COMMENT_MARKER}\nold`, id: 5, node_id: 'IC_5'}])
+        .delete('/repos/owner/repo/issues/comments/5')
+        .reply(204)
+
+      expect(await clearReportComment(octokit, pr, 'delete', 'abc123')).to.equal('deleted')
+      scope.done()
+    })
+
+    it('collapses the marked comment as outdated', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body: `${COMMENT_MARKER}\nold`, id: 5, node_id: 'IC_5'}])
+        .patch('/repos/owner/repo/issues/comments/5', (body) => body.body.includes('no signals fire as of abc123'))
+        .reply(200, {id: 5})
+        .post('/graphql', (body) => body.query.includes('classifier: OUTDATED') && body.variables.id === 'IC_5')
+        .reply(200, {data: {minimizeComment: {clientMutationId: null}}})
+
+      expect(await clearReportComment(octokit, pr, 'collapse', 'abc123')).to.equal('collapsed')
+      scope.done()
+    })
+
+    it('does nothing without a comment to clear', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body: 'LGTM', id: 1, node_id: 'IC_1'}])
+
+      expect(await clearReportComment(octokit, pr, 'delete', 'abc123')).to.equal('none')
+      scope.done()
+    })
+  })
+})
@@ -0,0 +1,92 @@
+import {expect} from 'chai'
+import nock from 'nock'
+import {Octokit} from 'octokit'
+
+import type {Stakeholder} from '../../../src/lib/configuration/config.js'
+import type {ReportOutput} from '../../../src/lib/reports/index.js'
+
+import {getNotifyStakeholders, sendGithubNotifications} from '../../../src/lib/github/notify.js'
+import {buildReport} from '../../helpers/reports.js'
+
+describe('github/notify', () => {
+  it('asks notify targets for a review', () => {
+    const reports: ReportOutput[] = [
+      {content: 'a', metadata: {diffText: '', fileName: 'a.ts', message: 'a'}, notify: {github: '@octocat'}},
+      {content: 'b', metadata: {diffText: '', fileName: 'b.ts', message: 'b'}, notify: {slack: '#alerts'}},
+    ]
+
+    expect(getNotifyStakeholders(reports)).to.deep.equal([
+      {contactMethod: 'github-reviewer-request', github: '@octocat', name: '@octocat'},
+    ])
+  })
+
+  it('contacts targets that are concern stakeholders per their contactMethod', () => {
+    const alice: Stakeholder = {contactMethod: 'github-comment-mention', github: 'alice', name: 'Alice'}
+    const reports: ReportOutput[] = [
+      {
+        content: 'a',
+        metadata: {diffText: '', fileName: 'a.ts', message: 'a', stakeholders: [alice]},
+        notify: {github: '@Alice'},
+      },
+    ]
+
+    expect(getNotifyStakeholders(reports)).to.deep.equal([alice])
+  })
+
+  describe('sendGithubNotifications', () => {
+    const pr = {number: 7, owner: 'owner', repo: 'repo'}
+
+    beforeEach(() => {
+      nock.disableNetConnect()
+    })
+
+    afterEach(() => {
+      nock.cleanAll()
+      nock.enableNetConnect()
+    })
+
+    it('requests reviews from targets and skips the author and requested reviewers', async () => {
+      const scope = nock('https://api.github.com')
+        // eslint-disable-next-line camelcase
+        .post('/repos/owner/repo/pulls/7/requested_reviewers', {reviewers: ['Octocat'], team_reviewers: ['security']})
+        .reply(201, {})
+      const targets = new Map([
+        ['@Octocat', [buildReport({content: 'a'})]],
+        ['@author', [buildReport({content: 'b'})]],
+        ['my-org/platform', [buildReport({content: 'c'})]],
+        ['my-org/security', [buildReport({content: 'd'}), buildReport({content: 'e'})]],
+      ])
+
+      const results = await sendGithubNotifications(new Octokit({auth: 'gh_token'}), pr, targets, {
+        author: 'author',
+        requestedTeams: ['platform'],
+      })
+
+      expect(results.map(({status, target}) => `${target} ${status}`)).to.deep.equal([
+        '@Octocat delivered',
+        '@author skipped',
+        'my-org/platform skipped',
+        'my-org/security delivered',
+      ])
+      expect(results[3].reports).to.equal(2)
+      scope.done()
+    })
+
+    it('reports the targets it could not contact as failed', async () => {
+      const scope = nock('https://api.github.com')
+        .post('/repos/owner/repo/pulls/7/requested_reviewers')
+        .reply(422, {message: 'Reviews may only be requested from collaborators.'})
+
+      const results = await sendGithubNotifications(
+        new Octokit({auth: 'gh_token'}),
+        pr,
+        new Map([['@octocat', [buildReport({content: 'a'})]]]),
+      )
+
+      expect(results).to.have.length(1)
+      expect(results[0]).to.include({channel: 'github', status: 'failed', target: '@octocat'})
+      expect(results[0].error).to.contain('Reviews may only be requested from collaborators.')
+      scope.done()
+    })
+  })
+})
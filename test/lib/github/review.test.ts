@@ -0,0 +1,173 @@
+/* eslint-disable camelcase */
+import {expect} from 'chai'
+import nock from 'nock'
+import {Octokit} from 'octokit'
+
+import {parseDiff} from '../../../src/lib/diff/parser.js'
+import {planReviewComments, postReview} from '../../../src/lib/github/review.js'
+import {getReportFingerprint} from '../../../src/lib/reports/index.js'
+import {buildReport} from '../../helpers/reports.js'
+
+const diff = `diff --git a/src/app.ts b/src/app.ts
+index 1111111..2222222 100644
+--- a/src/app.ts
++++ b/src/app.ts
+@@ -1,3 +1,3 @@
+ one
+-two
++two changed
+ three
+@@ -10,2 +10,3 @@
+ ten
++inserted
+ eleven
+`
+
+const inApp = {concernId: 'code', fileName: 'src/app.ts'}
+
+describe('github/review', () => {
+  const {files} = parseDiff(diff)
+
+  describe('planReviewComments', () => {
+    it('anchors findings on their changed lines', () => {
+      const plan = planReviewComments(
+        [
+          buildReport({...inApp, content: 'inserted', lines: {new: [11]}}),
+          buildReport({...inApp, content: 'removed', lines: {old: [2]}}),
+          buildReport({...inApp, content: 'context', lines: {new: [12]}}),
+        ],
+        files,
+        new Set(),
+      )
+
+      expect(plan.comments.map(({line, path, side}) => ({line, path, side}))).to.deep.equal([
+        {line: 11, path: 'src/app.ts', side: 'RIGHT'},
+        {line: 2, path: 'src/app.ts', side: 'LEFT'},
+        {line: 12, path: 'src/app.ts', side: 'RIGHT'},
+      ])
+      expect(plan.comments[0].body).to.match(/^inserted\n\n<!-- distill:finding:\w+ -->$/)
+    })
+
+    it('anchors findings after a missing newline at the end of the old file', () => {
+      const {files: [file]} = parseDiff(`diff --git a/src/app.ts b/src/app.ts
+index 1111111..2222222 100644
+--- a/src/app.ts
++++ b/src/app.ts
+@@ -1,2 +1,3 @@
+ one
+-two
+\\ No newline at end of file
++two
++three
+`)
+      const plan = planReviewComments(
+        [
+          buildReport({...inApp, content: 'appended', lines: {new: [3]}}),
+          buildReport({...inApp, content: 'rewritten', lines: {old: [2]}}),
+        ],
+        [file],
+        new Set(),
+      )
+
+      expect(plan.comments.map(({line, side}) => ({line, side}))).to.deep.equal([
+        {line: 3, side: 'RIGHT'},
+        {line: 2, side: 'LEFT'},
+      ])
+    })
+
+    it('anchors findings in a renamed file by its new name on both sides', () => {
+      const {files: [file]} = parseDiff(`diff --git a/src/old.ts b/src/new.ts
+similarity index 80%
+rename from src/old.ts
+rename to src/new.ts
+index 1111111..2222222 100644
+--- a/src/old.ts
++++ b/src/new.ts
+@@ -1,3 +1,3 @@
+ one
+-two
++two changed
+ three
+`)
+      const removed = {endColumn: 4, endLine: 2, path: 'src/old.ts', startColumn: 1, startLine: 2}
+      const plan = planReviewComments(
+        [buildReport({content: 'removed', fileName: 'src/new.ts', locations: {new: [], old: [removed]}})],
+        [file],
+        new Set(),
+      )
+
+      expect(plan.comments.map(({line, path, side}) => ({line, path, side}))).to.deep.equal([
+        {line: 2, path: 'src/new.ts', side: 'LEFT'},
+      ])
+    })
+
+    it('falls back to a file-level comment outside the diff', () => {
+      const farAway = buildReport({...inApp, content: 'far away', lines: {new: [40]}})
+      const plan = planReviewComments([farAway, buildReport({...inApp, content: 'no locations'})], files, new Set())
+
+      expect(plan.comments.map((comment) => comment.line)).to.deep.equal([undefined, undefined])
+      expect(plan.comments.map((comment) => comment.path)).to.deep.equal(['src/app.ts', 'src/app.ts'])
+    })
+
+    it('skips findings already commented on', () => {
+      const posted = buildReport({...inApp, content: 'posted', lines: {new: [11]}})
+      const repeated = buildReport({...inApp, content: 'repeated', lines: {new: [2]}})
+      const plan = planReviewComments(
+        [posted, repeated, repeated],
+        files,
+        new Set([getReportFingerprint(posted.metadata!)]),
+      )
+
+      expect(plan.duplicates).to.equal(2)
+      expect(plan.comments.map((comment) => comment.body.split('\n')[0])).to.deep.equal(['repeated'])
+    })
+  })
+
+  describe('postReview', () => {
+    beforeEach(() => {
+      nock.disableNetConnect()
+    })
+
+    afterEach(() => {
+      nock.cleanAll()
+      nock.enableNetConnect()
+    })
+
+    it('creates a review with inline comments and file-level fallbacks', async () => {
+      const posted = buildReport({...inApp, content: 'posted', lines: {new: [11]}})
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/pulls/7/comments')
+        .query(true)
+        .reply(200, [{body: `posted\n\n<!-- distill:finding:${getReportFingerprint(posted.metadata!)} -->`, id: 1}])
+        .post('/repos/owner/repo/pulls/7/reviews', {
+          body: 'distill found 2 new findings.',
+          comments: [{body: /^changed/, line: 2, path: 'src/app.ts', side: 'RIGHT'}],
+          commit_id: 'head-sha',
+          event: 'COMMENT',
+        })
+        .reply(200, {id: 10})
+        .post('/repos/owner/repo/pulls/7/comments', {
+          body: /^elsewhere/,
+          commit_id: 'head-sha',
+          path: 'src/app.ts',
+          subject_type: 'file',
+        })
+        .reply(201, {id: 11})
+
+      const plan = await postReview(
+        new Octokit({auth: 'gh_token'}),
+        {number: 7, owner: 'owner', repo: 'repo'},
+        'head-sha',
+        [
+          posted,
+          buildReport({...inApp, content: 'changed', lines: {new: [2]}}),
+          buildReport({...inApp, content: 'elsewhere'}),
+        ],
+        files,
+      )
+
+      expect(plan.duplicates).to.equal(1)
+      scope.done()
+    })
+  })
+})
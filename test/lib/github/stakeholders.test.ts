@@ -0,0 +1,158 @@
+import {expect} from 'chai'
+import nock from 'nock'
+import {Octokit} from 'octokit'
+
+import type {Stakeholder} from '../../../src/lib/configuration/config.js'
+
+import {
+  contactStakeholders,
+  describeContactPlan,
+  mentionMarker,
+  planContacts,
+  renderMentionComment,
+} from '../../../src/lib/github/stakeholders.js'
+
+describe('github/stakeholders', () => {
+  it('splits reviewer requests into users and teams', () => {
+    const plan = planContacts([
+      {contactMethod: 'github-reviewer-request', github: '@alice', name: 'Alice'},
+      {contactMethod: 'github-reviewer-request', github: 'my-org/security', name: 'Security Team'},
+    ])
+
+    expect(plan.reviewers).to.deep.equal(['alice'])
+    expect(plan.teamReviewers).to.deep.equal(['security'])
+    expect(plan.mentions).to.be.empty
+  })
+
+  it('contacts each stakeholder once', () => {
+    const security: Stakeholder = {contactMethod: 'github-comment-mention', github: 'my-org/security', name: 'Security'}
+    const plan = planContacts([security, security])
+
+    expect(plan.mentions).to.have.length(1)
+    expect(plan.mentions[0].handle).to.equal('@my-org/security')
+  })
+
+  it('does not request a review from the PR author', () => {
+    const plan = planContacts([{contactMethod: 'github-reviewer-request', github: 'Alice', name: 'Alice'}], {
+      author: 'alice',
+    })
+
+    expect(plan.reviewers).to.be.empty
+  })
+
+  it('collects stakeholders without a github handle as unreachable', () => {
+    const nobody: Stakeholder = {contactMethod: 'github-comment-mention', name: 'Nobody'}
+    const plan = planContacts([nobody])
+
+    expect(plan.unreachable).to.have.length(1)
+    expect(plan.mentions).to.be.empty
+  })
+
+  it('skips reviewers and teams whose review is already requested', () => {
+    const plan = planContacts(
+      [
+        {contactMethod: 'github-reviewer-request', github: '@Carol', name: 'Carol'},
+        {contactMethod: 'github-reviewer-request', github: 'dave', name: 'Dave'},
+        {contactMethod: 'github-reviewer-request', github: 'my-org/security', name: 'Security'},
+      ],
+      {requestedReviewers: ['carol'], requestedTeams: ['security']},
+    )
+
+    expect(plan.reviewers).to.deep.equal(['dave'])
+    expect(plan.teamReviewers).to.be.empty
+    expect(plan.alreadyRequested).to.deep.equal(['@Carol', '@my-org/security'])
+  })
+
+  it('describes a contact plan', () => {
+    const plan = planContacts([
+      {contactMethod: 'github-reviewer-request', github: 'dave', name: 'Dave'},
+      {contactMethod: 'github-reviewer-request', github: 'my-org/security', name: 'Security'},
+      {contactMethod: 'github-comment-mention', github: 'erin', name: 'Erin'},
+    ])
+
+    expect(describeContactPlan(plan)).to.deep.equal([
+      'Would request reviews from @dave',
+      'Would request reviews from teams security',
+      'Would mention @erin in a comment',
+    ])
+    expect(describeContactPlan(planContacts([]))).to.deep.equal(['No one to contact'])
+  })
+
+  it('renders a mention comment', () => {
+    const body = renderMentionComment([
+      {
+        handle: '@my-org/design',
+        stakeholder: {
+          contactMethod: 'github-comment-mention',
+          description: 'Owns components',
+          github: 'my-org/design',
+          name: 'Design',
+        },
+      },
+    ])
+
+    expect(body).to.contain('- @my-org/design (Design: Owns components)')
+  })
+
+  describe('contactStakeholders', () => {
+    const octokit = new Octokit({auth: 'gh_token'})
+    const pr = {number: 7, owner: 'owner', repo: 'repo'}
+    const plan = planContacts([{contactMethod: 'github-comment-mention', github: 'erin', name: 'Erin'}])
+    const body = renderMentionComment(plan.mentions)
+
+    beforeEach(() => {
+      nock.disableNetConnect()
+    })
+
+    afterEach(() => {
+      nock.cleanAll()
+      nock.enableNetConnect()
+    })
+
+    it('mentions stakeholders in a new comment', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body: 'LGTM', id: 1}])
+        .post('/repos/owner/repo/issues/7/comments', {body})
+        .reply(201, {id: 2})
+
+      await contactStakeholders(octokit, pr, plan)
+      scope.done()
+    })
+
+    it('updates the mention comment of an earlier run', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body: `${mentionMarker('stakeholders')}\n- @dave (Dave)`, id: 5}])
+        .patch('/repos/owner/repo/issues/comments/5', {body})
+        .reply(200, {id: 5})
+
+      await contactStakeholders(octokit, pr, plan)
+      scope.done()
+    })
+
+    it('does not mention the same stakeholders again', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body, id: 5}])
+
+      await contactStakeholders(octokit, pr, plan)
+      scope.done()
+    })
+
+    it('keeps notify targets in a comment of their own', async () => {
+      const scope = nock('https://api.github.com')
+        .get('/repos/owner/repo/issues/7/comments')
+        .query(true)
+        .reply(200, [{body, id: 5}])
+        .post('/repos/owner/repo/issues/7/comments', {body: renderMentionComment(plan.mentions, 'notify')})
+        .reply(201, {id: 6})
+
+      await contactStakeholders(octokit, pr, plan, 'notify')
+      scope.done()
+    })
+  })
+})
@@ -0,0 +1,84 @@
+import {expect} from 'chai'
+
+import {renderEmailDigest, sendEmailNotifications} from '../../../src/lib/notify/email.js'
+import {buildReport} from '../../helpers/reports.js'
+import {type SmtpSink, startSmtpSink} from '../../helpers/smtp-server.js'
+
+describe('notify/email', () => {
+  const source = {
+    pullRequest: {number: 7, url: 'https://github.com/owner/repo/pull/7'},
+    refs: {base: 'base-sha', head: 'head-sha'},
+    repository: 'owner/repo',
+  }
+
+  describe('renderEmailDigest', () => {
+    it('groups reports by concern in text and HTML parts', () => {
+      const digest = renderEmailDigest(
+        [
+          buildReport({content: 'Token <b>added</b>'}),
+          buildReport({concernId: 'deps', content: 'Lockfile changed', fileName: 'package.json'}),
+          buildReport({content: 'Key rotated', fileName: 'src/env.ts'}),
+        ],
+        source,
+      )
+
+      expect(digest.subject).to.equal('distill: 3 signals fired in owner/repo pull request #7')
+      expect(digest.text).to.contain('== security ==\n\nsrc/auth.ts\nToken <b>added</b>\n\nsrc/env.ts\nKey rotated')
+      expect(digest.text.indexOf('== security ==')).to.be.lessThan(digest.text.indexOf('== deps =='))
+      expect(digest.html).to.contain('<a href="https://github.com/owner/repo/pull/7">owner/repo pull request #7</a>')
+      expect(digest.html).to.contain('<pre>Token &lt;b&gt;added&lt;/b&gt;</pre>')
+    })
+  })
+
+  describe('sendEmailNotifications', () => {
+    let sink: SmtpSink
+
+    beforeEach(async () => {
+      sink = await startSmtpSink()
+    })
+
+    afterEach(async () => {
+      await sink.close()
+    })
+
+    it('sends one digest per recipient', async () => {
+      const results = await sendEmailNotifications(
+        new Map([
+          ['security@example.com', [buildReport({content: 'Secret added'})]],
+          ['platform@example.com', [buildReport({concernId: 'deps', content: 'Lockfile changed'})]],
+        ]),
+        {from: 'distill@example.com', host: '127.0.0.1', port: sink.port},
+        source,
+        {},
+      )
+
+      expect(results.map((result) => [result.target, result.status])).to.deep.equal([
+        ['security@example.com', 'delivered'],
+        ['platform@example.com', 'delivered'],
+      ])
+      expect(sink.messages).to.have.length(2)
+      expect(sink.messages[0].to).to.deep.equal(['<security@example.com>'])
+      expect(sink.messages[0].data).to.contain('Subject: distill: 1 signal fired in owner/repo pull request #7')
+      expect(sink.messages[0].data).to.contain('Content-Type: text/plain')
+      expect(sink.messages[0].data).to.contain('Content-Type: text/html')
+      expect(sink.messages[0].data).to.contain('Secret added')
+    })
+
+    it('reads the server from the environment', async () => {
+      const [result] = await sendEmailNotifications(
+        new Map([['security@example.com', [buildReport({content: 'Secret added'})]]]),
+        {},
+        source,
+        {SMTP_FROM: 'ci@example.com', SMTP_HOST: '127.0.0.1', SMTP_PORT: String(sink.port)},
+      )
+
+      expect(result.status).to.equal('delivered')
+      expect(sink.messages[0].from).to.equal('<ci@example.com>')
+    })
+
+    it('skips email without a host', async () => {
+      const [result] = await sendEmailNotifications(new Map([['a@example.com', []]]), {}, source, {})
+      expect(result.status).to.equal('skipped')
+    })
+  })
+})
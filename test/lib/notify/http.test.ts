@@ -0,0 +1,64 @@
+import {expect} from 'chai'
+
+import {createRateLimiter, DeliveryError, fetchWithRetry} from '../../../src/lib/notify/http.js'
+import {type LocalServer, startLocalServer} from '../../helpers/http-server.js'
+
+describe('notify/http', () => {
+  let server: LocalServer | undefined
+
+  afterEach(async () => {
+    await server?.close()
+    server = undefined
+  })
+
+  describe('fetchWithRetry', () => {
+    const options = {backoff: 1, retries: 2, timeout: 1000}
+
+    it('retries server errors until a request succeeds', async () => {
+      server = await startLocalServer((_request, response, index) => {
+        response.writeHead(index < 2 ? 503 : 200).end('ok')
+      })
+
+      const response = await fetchWithRetry(server.url, {method: 'POST'}, options)
+
+      expect(await response.text()).to.equal('ok')
+      expect(server.requests).to.have.length(3)
+    })
+
+    it('does not retry client errors', async () => {
+      server = await startLocalServer((_request, response) => {
+        response.writeHead(400).end('bad payload')
+      })
+
+      const error = await fetchWithRetry(server.url, {method: 'POST'}, options).catch((error_) => error_)
+
+      expect(error).to.be.instanceOf(DeliveryError)
+      expect(error.status).to.equal(400)
+      expect(error.message).to.contain('bad payload')
+      expect(server.requests).to.have.length(1)
+    })
+
+    it('gives up on requests that time out', async () => {
+      server = await startLocalServer(() => {
+        // Never respond
+      })
+
+      const impatient = {backoff: 1, retries: 1, timeout: 50}
+      const error = await fetchWithRetry(server.url, {}, impatient).catch((error_) => error_)
+
+      expect(error.message).to.equal('timed out after 50ms')
+      expect(server.requests).to.have.length(2)
+    })
+  })
+
+  describe('createRateLimiter', () => {
+    it('spaces out calls', async () => {
+      const waitTurn = createRateLimiter(30)
+      const start = Date.now()
+      await waitTurn()
+      await waitTurn()
+      await waitTurn()
+      expect(Date.now() - start).to.be.at.least(55)
+    })
+  })
+})
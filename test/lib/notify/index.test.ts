@@ -0,0 +1,98 @@
+import {expect} from 'chai'
+
+import {
+  createTemplateData,
+  type DeliveryResult,
+  planNotifications,
+  sendNotifications,
+} from '../../../src/lib/notify/index.js'
+import {getReportFingerprint} from '../../../src/lib/reports/index.js'
+import {buildReport} from '../../helpers/reports.js'
+
+describe('notify', () => {
+  describe('createTemplateData', () => {
+    it('groups reports by concern, with reports without a concern under other', () => {
+      const secret = buildReport({concernId: 'security', content: 'Secret added\n', fileName: 'src/app.ts'})
+      const token = buildReport({concernId: 'security', content: 'Token added', fileName: 'src/auth.ts'})
+      const loose = buildReport({concernId: undefined, content: 'Loose change', fileName: 'README.md'})
+
+      const data = createTemplateData([secret, token, loose], {refs: {base: 'main', head: 'feature'}})
+
+      expect(data.title).to.equal('distill: 3 signals fired')
+      expect(data.origin).to.equal('main..feature')
+      expect(data.concerns.map(({concernId, reports}) => [concernId, reports.map(({file}) => file)])).to.deep.equal([
+        ['security', ['src/app.ts', 'src/auth.ts']],
+        ['other', ['README.md']],
+      ])
+      expect(data.concerns[0].reports[0].content).to.equal('Secret added')
+    })
+
+    it('names the repository and pull request in the origin', () => {
+      const source = {
+        pullRequest: {number: 12, url: 'https://github.com/acme/app/pull/12'},
+        refs: {base: 'main', head: 'feature'},
+        repository: 'acme/app',
+      }
+
+      const data = createTemplateData([buildReport({content: 'Secret added'})], source)
+
+      expect(data.title).to.equal('distill: 1 signal fired')
+      expect(data.origin).to.equal('acme/app pull request #12')
+    })
+  })
+
+  describe('planNotifications', () => {
+    it('lists each channel target with the fingerprints of its reports', () => {
+      const secret = buildReport({content: 'Secret added', notify: {github: '@octocat', slack: '#security'}})
+      const token = buildReport({content: 'Token added', notify: {email: 'security@example.com', slack: '#security'}})
+
+      expect(planNotifications([secret, token])).to.deep.equal([
+        {channel: 'email', reports: [getReportFingerprint(token.metadata!)], target: 'security@example.com'},
+        {channel: 'github', reports: [getReportFingerprint(secret.metadata!)], target: '@octocat'},
+        {
+          channel: 'slack',
+          reports: [getReportFingerprint(secret.metadata!), getReportFingerprint(token.metadata!)],
+          target: '#security',
+        },
+      ])
+    })
+
+    it('lists a report found twice once', () => {
+      const report = buildReport({content: 'Secret added', notify: {webhook: 'https://hooks.example.com'}})
+
+      expect(planNotifications([report, report])[0].reports).to.have.length(1)
+    })
+  })
+
+  describe('sendNotifications', () => {
+    const source = {refs: {base: 'main', head: 'feature'}}
+
+    it('skips github targets without a github notifier', async () => {
+      const report = buildReport({content: 'Secret added', notify: {github: '@octocat'}})
+
+      expect(await sendNotifications([report], {}, source)).to.deep.equal([
+        {
+          channel: 'github',
+          error: 'GitHub targets are only contacted by distill pr',
+          reports: 1,
+          status: 'skipped',
+          target: '@octocat',
+        },
+      ])
+    })
+
+    it('passes github targets to the github notifier', async () => {
+      const report = buildReport({content: 'Secret added', notify: {github: '@octocat'}})
+      const delivered: DeliveryResult = {channel: 'github', reports: 1, status: 'delivered', target: '@octocat'}
+      let received: Map<string, unknown[]> | undefined
+
+      const results = await sendNotifications([report], {}, source, async (targets) => {
+        received = targets
+        return [delivered]
+      })
+
+      expect(results).to.deep.equal([delivered])
+      expect([...received!]).to.deep.equal([['@octocat', [report]]])
+    })
+  })
+})
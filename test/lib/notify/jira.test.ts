@@ -0,0 +1,152 @@
+import {expect} from 'chai'
+
+import {getJiraLabels, sendJiraNotifications} from '../../../src/lib/notify/jira.js'
+import {type LocalServer, type ReceivedRequest, startLocalServer} from '../../helpers/http-server.js'
+import {buildReport} from '../../helpers/reports.js'
+
+describe('notify/jira', () => {
+  const source = {
+    pullRequest: {number: 7, url: 'https://github.com/owner/repo/pull/7'},
+    refs: {base: 'base-sha', head: 'head-sha'},
+    repository: 'owner/repo',
+  }
+  const report = buildReport({content: 'Secret added'})
+  let server: LocalServer | undefined
+
+  afterEach(async () => {
+    await server?.close()
+    server = undefined
+  })
+
+  /**
+   * Answer like a Jira site with one issue, SEC-1, whose comments are kept between runs.
+   */
+  async function startJira(
+    options: {createStatus?: number; labelled?: boolean; server?: boolean} = {},
+  ): Promise<LocalServer> {
+    const comments: Array<{body: string}> = []
+    let description: null | string = null
+    return startLocalServer((request: ReceivedRequest, response) => {
+      const reply = (status: number, body: unknown) => {
+        response.writeHead(status, {'content-type': 'application/json'}).end(JSON.stringify(body))
+      }
+
+      // Jira Server and Data Center only search at /search
+      const searchPath = options.server ? '/rest/api/2/search?' : '/rest/api/2/search/jql?'
+      if (request.url?.startsWith(searchPath)) {
+        reply(200, {issues: options.labelled ? [{key: 'SEC-1'}] : []})
+      } else if (request.method === 'POST' && request.url === '/rest/api/2/issue') {
+        description = JSON.parse(request.body).fields.description
+        reply(options.createStatus ?? 201, {key: 'SEC-1'})
+      } else if (request.method === 'POST' && request.url === '/rest/api/2/issue/SEC-1/comment') {
+        comments.push({body: JSON.parse(request.body).body})
+        reply(201, {})
+      } else if (request.url?.startsWith('/rest/api/2/issue/SEC-1?')) {
+        reply(200, {fields: {comment: {comments}, description}})
+      } else {
+        reply(404, {errorMessages: ['Issue does not exist']})
+      }
+    })
+  }
+
+  it('derives labels from concern ids', () => {
+    const reports = [buildReport(), buildReport({concernId: 'api contracts'}), buildReport()]
+    const labels = getJiraLabels(reports)
+    expect(labels).to.deep.equal(['distill', 'distill-security', 'distill-api-contracts'])
+  })
+
+  it('comments on an issue key once per set of reports', async () => {
+    server = await startJira()
+    const env = {JIRA_API_TOKEN: 'token', JIRA_EMAIL: 'bot@example.com', JIRA_URL: server.url}
+
+    const first = await sendJiraNotifications(new Map([['SEC-1', [report]]]), {}, source, env)
+    const second = await sendJiraNotifications(new Map([['SEC-1', [report]]]), {}, source, env)
+
+    expect([...first, ...second].map((result) => result.status)).to.deep.equal(['delivered', 'delivered'])
+    const posts = server.requests.filter((request) => request.method === 'POST')
+    expect(posts).to.have.length(1)
+    expect(posts[0].headers.authorization).to.equal(`Basic ${Buffer.from('bot@example.com:token').toString('base64')}`)
+    const {body} = JSON.parse(posts[0].body)
+    expect(body).to.contain('[owner/repo pull request #7|https://github.com/owner/repo/pull/7]')
+    expect(body).to.contain('h3. security')
+    expect(body).to.match(/_distill fingerprint: [\da-f]{16}_$/)
+  })
+
+  it('creates an issue in a project key with templated fields', async () => {
+    server = await startJira()
+
+    const [result] = await sendJiraNotifications(
+      new Map([['SEC', [report]]]),
+      {issueType: 'Bug', summary: 'Review {{origin}}', url: server.url},
+      source,
+      {JIRA_API_TOKEN: 'pat'},
+    )
+
+    expect(result.status).to.equal('delivered')
+    const search = server.requests.find((request) => request.url?.startsWith('/rest/api/2/search/jql'))
+    expect(decodeURIComponent(search?.url ?? '')).to.match(/project = "SEC" AND labels = "distill-fp-[\da-f]{16}"/)
+    const create = server.requests.find((request) => request.method === 'POST')
+    expect(create?.headers.authorization).to.equal('Bearer pat')
+    const {fields} = JSON.parse(create?.body ?? '{}')
+    expect(fields.summary).to.equal('Review owner/repo pull request #7')
+    expect(fields.issuetype).to.deep.equal({name: 'Bug'})
+    expect(fields.project).to.deep.equal({key: 'SEC'})
+    expect(fields.labels.slice(0, 2)).to.deep.equal(['distill', 'distill-security'])
+    expect(fields.labels[2]).to.match(/^distill-fp-[\da-f]{16}$/)
+    expect(server.requests.filter((request) => request.method === 'POST')).to.have.length(1)
+  })
+
+  it('comments on the issue already created for the pull request', async () => {
+    server = await startJira({labelled: true})
+
+    await sendJiraNotifications(new Map([['SEC', [report]]]), {url: server.url}, source, {JIRA_API_TOKEN: 'pat'})
+
+    const posts = server.requests.filter((request) => request.method === 'POST')
+    expect(posts.map((request) => request.url)).to.deep.equal(['/rest/api/2/issue/SEC-1/comment'])
+  })
+
+  it('searches at /search on Jira Server and Data Center', async () => {
+    server = await startJira({labelled: true, server: true})
+
+    const env = {JIRA_API_TOKEN: 'pat'}
+    const targets = new Map([
+      ['OPS', [report]],
+      ['SEC', [report]],
+    ])
+    const results = await sendJiraNotifications(targets, {url: server.url}, source, env)
+
+    expect(results.map((result) => result.status)).to.deep.equal(['delivered', 'delivered'])
+    const searches = server.requests.filter((request) => request.url?.startsWith('/rest/api/2/search'))
+    expect(searches.map((request) => request.url?.split('?')[0])).to.deep.equal([
+      '/rest/api/2/search/jql',
+      '/rest/api/2/search',
+      '/rest/api/2/search',
+    ])
+  })
+
+  it('does not retry creating an issue', async () => {
+    server = await startJira({createStatus: 503})
+
+    const env = {JIRA_API_TOKEN: 'pat'}
+    const [result] = await sendJiraNotifications(new Map([['SEC', [report]]]), {url: server.url}, source, env)
+
+    expect(result.status).to.equal('failed')
+    expect(result.error).to.match(/^503/)
+    expect(server.requests.filter((request) => request.method === 'POST')).to.have.length(1)
+  })
+
+  it('fails targets that are not issue or project keys', async () => {
+    server = await startJira()
+
+    const env = {JIRA_API_TOKEN: 'pat'}
+    const [result] = await sendJiraNotifications(new Map([['sec 1', [report]]]), {url: server.url}, source, env)
+
+    expect(result.status).to.equal('failed')
+    expect(server.requests).to.have.length(0)
+  })
+
+  it('skips Jira without credentials', async () => {
+    const [result] = await sendJiraNotifications(new Map([['SEC-1', [report]]]), {}, source, {})
+    expect(result.status).to.equal('skipped')
+  })
+})
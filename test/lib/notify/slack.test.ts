@@ -0,0 +1,116 @@
+import {expect} from 'chai'
+
+import {renderSlackMessage, sendSlackNotifications} from '../../../src/lib/notify/slack.js'
+import {type LocalServer, startLocalServer} from '../../helpers/http-server.js'
+import {buildReport} from '../../helpers/reports.js'
+
+describe('notify/slack', () => {
+  const source = {
+    pullRequest: {number: 7, url: 'https://github.com/owner/repo/pull/7'},
+    refs: {base: 'base-sha', head: 'head-sha'},
+    repository: 'owner/repo',
+  }
+  let server: LocalServer | undefined
+
+  afterEach(async () => {
+    await server?.close()
+    server = undefined
+  })
+
+  describe('renderSlackMessage', () => {
+    it('renders a section per report with a link to the pull request', () => {
+      const message = renderSlackMessage([buildReport({content: 'Token <redacted> added'})], source)
+
+      expect(message.text).to.equal('distill: 1 signal fired')
+      expect(message.blocks[1]).to.deep.equal({
+        elements: [{text: '*owner/repo* · <https://github.com/owner/repo/pull/7|Pull request #7>', type: 'mrkdwn'}],
+        type: 'context',
+      })
+      expect(message.blocks[2]).to.deep.equal({
+        text: {text: '*security* · `src/auth.ts`\nToken &lt;redacted&gt; added', type: 'mrkdwn'},
+        type: 'section',
+      })
+    })
+
+    it('stays within the block limit', () => {
+      const reports = Array.from({length: 60}, (_, index) => buildReport({content: `Finding ${index}`}))
+      const {blocks} = renderSlackMessage(reports, {refs: {base: 'main', head: 'HEAD'}})
+
+      expect(blocks).to.have.length(50)
+      expect(blocks.at(-1)).to.deep.include({type: 'context'})
+      expect(JSON.stringify(blocks.at(-1))).to.contain('and 13 more')
+    })
+  })
+
+  describe('sendSlackNotifications', () => {
+    it('posts to an incoming webhook', async () => {
+      server = await startLocalServer((_request, response) => {
+        response.writeHead(200).end('ok')
+      })
+
+      const results = await sendSlackNotifications(
+        new Map([['#security', [buildReport({content: 'Secret added'})]]]),
+        {interval: 0},
+        source,
+        {webhookUrl: `${server.url}/hooks/abc`},
+      )
+
+      expect(results).to.deep.equal([{channel: 'slack', reports: 1, status: 'delivered', target: '#security'}])
+      expect(server.requests[0].url).to.equal('/hooks/abc')
+      expect(JSON.parse(server.requests[0].body).blocks[0].text.text).to.equal('distill: 1 signal fired')
+    })
+
+    it('posts to each channel with a bot token, retrying when rate limited', async () => {
+      server = await startLocalServer((_request, response, index) => {
+        if (index === 0) {
+          response.writeHead(429, {'retry-after': '0'}).end()
+          return
+        }
+
+        response.writeHead(200, {'content-type': 'application/json'}).end(JSON.stringify({ok: true}))
+      })
+
+      const results = await sendSlackNotifications(
+        new Map([
+          ['#deps', [buildReport({content: 'Lockfile changed', fileName: 'package-lock.json'})]],
+          ['#security', [buildReport({content: 'Secret added'})]],
+        ]),
+        {apiUrl: server.url, interval: 0},
+        source,
+        {token: 'xoxb-test'},
+      )
+
+      expect(results.map((result) => result.status)).to.deep.equal(['delivered', 'delivered'])
+      expect(server.requests).to.have.length(3)
+      expect(server.requests[1].url).to.equal('/chat.postMessage')
+      expect(server.requests[1].headers.authorization).to.equal('Bearer xoxb-test')
+      expect(server.requests.map((request) => JSON.parse(request.body).channel)).to.deep.equal([
+        '#deps',
+        '#deps',
+        '#security',
+      ])
+    })
+
+    it('reports Web API errors as failures', async () => {
+      server = await startLocalServer((_request, response) => {
+        response.writeHead(200, {'content-type': 'application/json'}).end(
+          JSON.stringify({error: 'channel_not_found', ok: false}),
+        )
+      })
+
+      const [result] = await sendSlackNotifications(
+        new Map([['#nowhere', [buildReport({content: 'Secret added'})]]]),
+        {apiUrl: server.url, interval: 0},
+        source,
+        {token: 'xoxb-test'},
+      )
+
+      expect(result).to.deep.include({error: 'channel_not_found', status: 'failed'})
+    })
+
+    it('skips Slack without credentials', async () => {
+      const [result] = await sendSlackNotifications(new Map([['#security', [buildReport()]]]), {}, source, {})
+      expect(result.status).to.equal('skipped')
+    })
+  })
+})
@@ -0,0 +1,72 @@
+import {expect} from 'chai'
+
+import {sendWebhookNotifications, signWebhookBody} from '../../../src/lib/notify/webhook.js'
+import {type LocalServer, startLocalServer} from '../../helpers/http-server.js'
+import {buildReport} from '../../helpers/reports.js'
+
+const report = buildReport({content: 'Secret added in src/auth.ts', severity: 'error', signalId: 'secrets'})
+
+describe('notify/webhook', () => {
+  const source = {refs: {base: 'base-sha', head: 'head-sha'}, repository: 'owner/repo'}
+  let server: LocalServer | undefined
+
+  afterEach(async () => {
+    await server?.close()
+    server = undefined
+  })
+
+  it('posts a signed payload with the reports', async () => {
+    server = await startLocalServer((_request, response) => {
+      response.writeHead(204).end()
+    })
+
+    const results = await sendWebhookNotifications(new Map([[`${server.url}/hook`, [report]]]), {}, source, 's3cret')
+
+    expect(results).to.deep.equal([{channel: 'webhook', reports: 1, status: 'delivered', target: `${server.url}/hook`}])
+    const [request] = server.requests
+    expect(JSON.parse(request.body)).to.deep.equal({
+      refs: {base: 'base-sha', head: 'head-sha'},
+      reports: [
+        {
+          concern: 'security',
+          content: 'Secret added in src/auth.ts',
+          file: 'src/auth.ts',
+          message: 'Secret added in src/auth.ts',
+          severity: 'error',
+          signal: 'secrets',
+        },
+      ],
+      repository: 'owner/repo',
+    })
+    expect(request.headers['x-distill-signature-256']).to.equal(signWebhookBody(request.body, 's3cret'))
+    expect(request.headers['x-distill-delivery']).to.be.a('string')
+  })
+
+  it('retries with the same delivery id, then reports the failure', async () => {
+    server = await startLocalServer((_request, response) => {
+      response.writeHead(502).end('bad gateway')
+    })
+
+    const [result] = await sendWebhookNotifications(new Map([[server.url, [report]]]), {backoff: 1, retries: 2}, source)
+
+    expect(result).to.deep.include({status: 'failed'})
+    expect(result.error).to.contain('502')
+    expect(server.requests).to.have.length(3)
+    expect(new Set(server.requests.map((request) => request.headers['x-distill-delivery']))).to.have.property('size', 1)
+    expect(server.requests[0].headers).to.not.have.property('x-distill-signature-256')
+  })
+
+  it('gives up on receivers slower than the timeout', async () => {
+    server = await startLocalServer(() => {
+      // Never respond
+    })
+
+    const [result] = await sendWebhookNotifications(
+      new Map([[server.url, [report]]]),
+      {backoff: 1, retries: 0, timeout: 50},
+      source,
+    )
+
+    expect(result).to.deep.include({error: 'timed out after 50ms', status: 'failed'})
+  })
+})
@@ -52,4 +52,271 @@ describe('Concerns Processing', () => {
     expect(result.reports).to.have.length(1)
     expect(result.reports[0].content).to.include('Found foo')
   })
+
+  it('identifies the concern, signal and watch type of each report', async () => {
+    const watch = {include: '*.ts', pattern: 'foo', type: 'regex' as const}
+    const report = {template: '{{concernId}}/{{signalId}}/{{watchType}}', type: 'handlebars' as const}
+    const config: DistillConfig = {
+      concerns: {
+        'my-concern': {
+          signals: [{report, watch}, {id: 'named', report, watch}, {use: '#defined/signals/shared'}],
+        },
+      },
+      defined: {signals: {shared: {report, watch}}},
+    }
+
+    const context: ProcessingContext = {
+      contentProvider: async (ref) => (ref === 'HEAD' ? 'foo' : ''),
+      refs: {base: 'BASE', head: 'HEAD'},
+    }
+
+    const file: File = {
+      hunks: [],
+      newEndingNewLine: true,
+      newMode: '100644',
+      newPath: 'test.ts',
+      newRevision: 'def',
+      oldEndingNewLine: true,
+      oldMode: '100644',
+      oldPath: 'test.ts',
+      oldRevision: 'abc',
+      type: 'modify',
+    }
+
+    const result = await processFiles([file], config, context)
+
+    expect(result.reports.map((r) => r.content)).to.deep.equal([
+      'my-concern/0/regex',
+      'my-concern/named/regex',
+      'my-concern/#defined/signals/shared/regex',
+    ])
+    expect(result.reports[1].metadata).to.deep.include({concernId: 'my-concern', signalId: 'named', watchType: 'regex'})
+  })
+
+  it('applies global ignore and concern-level include/exclude filters', async () => {
+    const signals = [
+      {
+        report: {template: '{{filePath}}', type: 'handlebars' as const},
+        watch: {exclude: '**/*.test.ts', include: '**/*.ts', pattern: 'foo', type: 'regex' as const},
+      },
+    ]
+    const config: DistillConfig = {
+      concerns: {
+        everything: {signals},
+        'payments-only': {exclude: '**/legacy/**', include: 'payments/**', signals},
+      },
+      ignore: ['vendor/**'],
+    }
+
+    const context: ProcessingContext = {
+      contentProvider: async (ref) => (ref === 'HEAD' ? 'foo' : ''),
+      refs: {base: 'BASE', head: 'HEAD'},
+    }
+
+    const paths = ['test.ts', 'payments/api.ts', 'payments/api.test.ts', 'payments/legacy/old.ts', 'vendor/lib.ts']
+    const files = paths.map(
+      (path): File => ({
+        hunks: [],
+        newEndingNewLine: true,
+        newMode: '100644',
+        newPath: path,
+        newRevision: 'def',
+        oldEndingNewLine: true,
+        oldMode: '100644',
+        oldPath: path,
+        oldRevision: 'abc',
+        type: 'modify',
+      }),
+    )
+    const result = await processFiles(files, config, context)
+
+    expect(result.reports.map((r) => `${r.metadata?.concernId}:${r.content}`)).to.deep.equal([
+      'everything:test.ts',
+      'everything:payments/api.ts',
+      'payments-only:payments/api.ts',
+      'everything:payments/legacy/old.ts',
+    ])
+  })
+
+  it('carries concern stakeholders into reports', async () => {
+    const config: DistillConfig = {
+      concerns: {
+        'my-concern': {
+          signals: [
+            {
+              report: {
+                template: '{{#each stakeholders}}{{name}} via {{contactMethod}}{{/each}}',
+                type: 'handlebars',
+              },
+              watch: {
+                include: '*.ts',
+                pattern: 'foo',
+                type: 'regex',
+              },
+            },
+          ],
+          stakeholders: [{contactMethod: 'github-reviewer-request', github: 'my-org/team', name: 'The Team'}],
+        },
+      },
+    }
+
+    const context: ProcessingContext = {
+      contentProvider: async (ref) => (ref === 'HEAD' ? 'foo' : ''),
+      refs: {base: 'BASE', head: 'HEAD'},
+    }
+
+    const file: File = {
+      hunks: [],
+      newEndingNewLine: true,
+      newMode: '100644',
+      newPath: 'test.ts',
+      newRevision: 'def',
+      oldEndingNewLine: true,
+      oldMode: '100644',
+      oldPath: 'test.ts',
+      oldRevision: 'abc',
+      type: 'modify',
+    }
+
+    const result = await processFiles([file], config, context)
+
+    expect(result.reports).to.have.length(1)
+    expect(result.reports[0].content).to.equal('The Team via github-reviewer-request')
+    expect(result.reports[0].metadata?.stakeholders).to.deep.equal([
+      {contactMethod: 'github-reviewer-request', github: 'my-org/team', name: 'The Team'},
+    ])
+  })
+
+  it('resolves severity from the signal, then the concern', async () => {
+    const watch = {include: '*.ts', pattern: 'foo', type: 'regex'} as const
+    const config: DistillConfig = {
+      concerns: {
+        plain: {signals: [{report: {template: 'plain', type: 'handlebars'}, watch}]},
+        strict: {
+          severity: 'warning',
+          signals: [
+            {report: {template: 'inherited', type: 'handlebars'}, watch},
+            {report: {template: '{{severity}}', type: 'handlebars'}, severity: 'error', watch},
+          ],
+        },
+      },
+    }
+
+    const context: ProcessingContext = {
+      contentProvider: async (ref) => (ref === 'HEAD' ? 'foo' : ''),
+      refs: {base: 'BASE', head: 'HEAD'},
+    }
+
+    const file: File = {
+      hunks: [],
+      newEndingNewLine: true,
+      newMode: '100644',
+      newPath: 'test.ts',
+      newRevision: 'def',
+      oldEndingNewLine: true,
+      oldMode: '100644',
+      oldPath: 'test.ts',
+      oldRevision: 'abc',
+      type: 'modify',
+    }
+
+    const result = await processFiles([file], config, context)
+
+    expect(result.reports.map((r) => `${r.content}:${r.metadata?.severity}`)).to.deep.equal([
+      'plain:info',
+      'inherited:warning',
+      'error:error',
+    ])
+  })
+
+  it('renders notify targets with the report data', async () => {
+    const config: DistillConfig = {
+      concerns: {
+        services: {
+          signals: [
+            {
+              notify: {
+                github: '{{right.captures.[0].owner}}',
+                jira: '{{#if right.captures.[0].ticket}}{{right.captures.[0].ticket}}{{/if}}',
+                slack: '#team-{{pathSegments.[1]}}',
+              },
+              report: {template: 'Owner changed in {{filePath}}', type: 'handlebars'},
+              watch: {include: 'services/*/**', pattern: 'owner: (?<owner>@[\\w-]+)', type: 'regex'},
+            },
+          ],
+        },
+      },
+    }
+
+    const context: ProcessingContext = {
+      contentProvider: async (ref) => (ref === 'HEAD' ? 'owner: @api-team' : ''),
+      refs: {base: 'BASE', head: 'HEAD'},
+    }
+
+    const file: File = {
+      hunks: [],
+      newEndingNewLine: true,
+      newMode: '100644',
+      newPath: 'services/api/OWNERS',
+      newRevision: 'def',
+      oldEndingNewLine: true,
+      oldMode: '100644',
+      oldPath: 'services/api/OWNERS',
+      oldRevision: 'abc',
+      type: 'modify',
+    }
+
+    const result = await processFiles([file], config, context)
+
+    expect(result.reports).to.have.length(1)
+    expect(result.reports[0].notify).to.deep.equal({github: '@api-team', slack: '#team-api'})
+  })
+
+  it('collects signals that fail on a file and carries on with the others', async () => {
+    const config: DistillConfig = {
+      concerns: {
+        deps: {
+          signals: [
+            {
+              id: 'runtime-dependencies',
+              report: {template: 'x', type: 'handlebars'},
+              watch: {include: 'package.json', query: '.dependencies', type: 'jq'},
+            },
+            {
+              id: 'any-change',
+              report: {template: 'Changed {{filePath}}', type: 'handlebars'},
+              watch: {include: 'package.json', pattern: 'dependencies', type: 'regex'},
+            },
+          ],
+        },
+      },
+    }
+
+    const context: ProcessingContext = {
+      contentProvider: async (ref) => (ref === 'HEAD' ? '{"dependencies": ' : ''),
+      refs: {base: 'BASE', head: 'HEAD'},
+    }
+
+    const file: File = {
+      hunks: [],
+      newEndingNewLine: true,
+      newMode: '100644',
+      newPath: 'package.json',
+      newRevision: 'def',
+      oldEndingNewLine: true,
+      oldMode: '100644',
+      oldPath: 'package.json',
+      oldRevision: 'abc',
+      type: 'modify',
+    }
+
+    const result = await processFiles([file], config, context)
+
+    expect(result.errors).to.have.length(1)
+    expect(result.errors[0]).to.include({concernId: 'deps', filePath: 'package.json', signalId: 'runtime-dependencies'})
+    expect(result.errors[0].message).to.include(
+      'Signal "runtime-dependencies" of concern "deps" failed on package.json (jq watch)',
+    )
+    expect(result.reports.map((r) => r.content)).to.deep.equal(['Changed package.json'])
+  })
 })
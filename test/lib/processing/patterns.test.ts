@@ -0,0 +1,32 @@
+import {expect} from 'chai'
+
+import {matchesPatterns} from '../../../src/lib/processing/patterns.js'
+
+describe('matchesPatterns', () => {
+  it('matches any of the include patterns', () => {
+    expect(matchesPatterns('package.json', {include: ['package.json', 'yarn.lock']})).to.be.true
+    expect(matchesPatterns('src/index.ts', {include: 'src/**/*.ts'})).to.be.true
+    expect(matchesPatterns('README.md', {include: 'src/**/*.ts'})).to.be.false
+  })
+
+  it('skips files matching exclude patterns', () => {
+    const patterns = {exclude: ['**/*.test.ts', '**/generated/**'], include: 'src/**/*.ts'}
+
+    expect(matchesPatterns('src/index.ts', patterns)).to.be.true
+    expect(matchesPatterns('src/index.test.ts', patterns)).to.be.false
+    expect(matchesPatterns('src/generated/client.ts', patterns)).to.be.false
+  })
+
+  it('treats negated include patterns as excludes regardless of order', () => {
+    expect(matchesPatterns('src/index.ts', {include: ['!**/*.test.ts', 'src/**/*.ts']})).to.be.true
+    expect(matchesPatterns('src/index.test.ts', {include: ['src/**/*.ts', '!**/*.test.ts']})).to.be.false
+    // A negated pattern alone must not select unrelated files alongside a positive pattern
+    expect(matchesPatterns('README.md', {include: ['src/**/*.ts', '!**/*.test.ts']})).to.be.false
+  })
+
+  it('selects everything not excluded when there are no positive include patterns', () => {
+    expect(matchesPatterns('src/index.ts', {})).to.be.true
+    expect(matchesPatterns('vendor/lib.js', {exclude: 'vendor/**'})).to.be.false
+    expect(matchesPatterns('src/index.ts', {include: '!**/*.test.ts'})).to.be.true
+  })
+})
@@ -0,0 +1,86 @@
+import {expect} from 'chai'
+
+import {parseDiff} from '../../../src/lib/diff/parser.js'
+import {applyScope, getChangedLines} from '../../../src/lib/processing/scope.js'
+import {regexFilter} from '../../../src/lib/watches/index.js'
+
+const oldContent = ['// TODO: a', 'const a = 1', '', '', '', '// TODO: b', 'const b = 2', ''].join('\n')
+const newContent = ['// TODO: a', 'const a = 1', '', '', '', '// TODO: b', 'const b = 3', '// TODO: c', ''].join('\n')
+
+const [file] = parseDiff(`diff --git a/src/app.ts b/src/app.ts
+index 1111111..2222222 100644
+--- a/src/app.ts
++++ b/src/app.ts
+@@ -5,3 +5,4 @@
+ 
+ // TODO: b
+-const b = 2
++const b = 3
++// TODO: c
+`).files
+
+describe('processing/scope', () => {
+  it('collects changed lines and hunk lines on each side', () => {
+    expect(getChangedLines(file, 'changed-lines')).to.deep.equal({new: new Set([7, 8]), old: new Set([7])})
+    expect(getChangedLines(file, 'changed-hunks')).to.deep.equal({
+      new: new Set([5, 6, 7, 8]),
+      old: new Set([5, 6, 7]),
+    })
+  })
+
+  it('keeps only matches overlapping changed lines', async () => {
+    const result = await regexFilter.apply({newContent, oldContent}, {pattern: '^// TODO: .*$', type: 'regex'})
+
+    const scoped = await applyScope(result!, file, 'changed-lines')
+
+    expect(scoped!.left.artifact).to.equal('')
+    expect(scoped!.right.artifact).to.equal('// TODO: c')
+    expect(scoped!.right.matches!.map((match) => match.range.startLine)).to.deep.equal([8])
+  })
+
+  it('keeps only the captures of matches in scope', async () => {
+    const result = await regexFilter.apply({newContent, oldContent}, {pattern: '^// TODO: (?<task>.*)$', type: 'regex'})
+
+    const scoped = await applyScope(result!, file, 'changed-lines')
+
+    expect(result!.right.captures).to.deep.equal([{task: 'a'}, {task: 'b'}, {task: 'c'}])
+    expect(scoped!.left.captures).to.deep.equal([])
+    expect(scoped!.right.captures).to.deep.equal([{task: 'c'}])
+  })
+
+  it('includes context lines with changed-hunks', async () => {
+    const result = await regexFilter.apply({newContent, oldContent}, {pattern: '^// TODO: .*$', type: 'regex'})
+
+    const scoped = await applyScope(result!, file, 'changed-hunks')
+
+    expect(scoped!.left.artifact).to.equal('// TODO: b')
+    expect(scoped!.right.artifact).to.equal('// TODO: b\n// TODO: c')
+  })
+
+  it('returns null when no changed match remains', async () => {
+    const result = await regexFilter.apply(
+      {newContent: `${newContent}// TODO: d\n`, oldContent},
+      {pattern: '^// TODO: [ad]$', type: 'regex'},
+    )
+
+    // `TODO: d` is outside the diff above, so only the unchanged `TODO: a` could be in scope
+    expect(await applyScope(result!, file, 'changed-lines')).to.be.null
+  })
+
+  it('leaves results unchanged with the file scope', async () => {
+    const result = {diffText: 'diff', left: {artifact: '1'}, right: {artifact: '2'}}
+
+    expect(await applyScope(result, file, 'file')).to.equal(result)
+  })
+
+  it('rejects narrowing results without match locations', async () => {
+    const result = {diffText: 'diff', left: {artifact: '1'}, right: {artifact: '2'}}
+
+    try {
+      await applyScope(result, file, 'changed-lines')
+      expect.fail('Should have thrown')
+    } catch (error) {
+      expect((error as Error).message).to.include('scope "changed-lines" needs located matches')
+    }
+  })
+})
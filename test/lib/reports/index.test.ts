@@ -1,7 +1,7 @@
 import {expect} from 'chai'
 
 import {FilterResult} from '../../../src/lib/configuration/config.js'
-import {executeReport, HandlebarsReport} from '../../../src/lib/reports/index.js'
+import {executeReport, HandlebarsReport, JsonReport, validateReport} from '../../../src/lib/reports/index.js'
 
 describe('Reports', () => {
   describe('executeReport', () => {
@@ -58,5 +58,124 @@ describe('Reports', () => {
         start: 20,
       })
     })
+
+    it('exposes captures of each side to templates', () => {
+      const report: HandlebarsReport = {
+        template: '{{#each right.captures}}{{FN}}({{ARGS}}) {{/each}}',
+        type: 'handlebars',
+      }
+
+      const filterResult: FilterResult = {
+        diffText: 'some diff',
+        left: {artifact: 'fetch(a)', captures: [{ARGS: 'a', FN: 'fetch'}]},
+        right: {artifact: 'fetch(a, b)', captures: [{ARGS: 'a, b', FN: 'fetch'}]},
+      }
+
+      const output = executeReport(report, filterResult, {filePath: 'src/main.ts'})
+
+      expect(output.content).to.equal('fetch(a, b) ')
+    })
+
+    it('locates changed matches in the old and new file', () => {
+      const report: HandlebarsReport = {
+        template: '{{#each locations.new}}{{path}}:{{startLine}} {{/each}}',
+        type: 'handlebars',
+      }
+
+      const range = (line: number) => ({endColumn: 10, endLine: line, startColumn: 1, startLine: line})
+      const filterResult: FilterResult = {
+        diffText: 'some diff',
+        left: {
+          artifact: 'same\nremoved',
+          matches: [
+            {range: range(1), text: 'same'},
+            {range: range(2), text: 'removed'},
+          ],
+        },
+        right: {
+          artifact: 'same\nadded',
+          matches: [
+            {range: range(3), text: 'same'},
+            {range: range(7), text: 'added'},
+          ],
+        },
+      }
+
+      const output = executeReport(report, filterResult, {filePath: 'src/new.ts', oldFilePath: 'src/old.ts'})
+
+      expect(output.content).to.equal('src/new.ts:7 ')
+      expect(output.metadata?.locations).to.deep.equal({
+        new: [{path: 'src/new.ts', ...range(7)}],
+        old: [{path: 'src/old.ts', ...range(2)}],
+      })
+    })
+  })
+
+  describe('json report', () => {
+    const filterResult: FilterResult = {
+      diffText: '-a\n+b',
+      left: {artifact: 'serde = "1.0.100"', captures: [{name: 'serde', version: '1.0.100'}]},
+      right: {artifact: 'serde = "1.0.200"', captures: [{name: 'serde', version: '1.0.200'}]},
+    }
+
+    it('produces an object from templates and selectors', () => {
+      const report: JsonReport = {
+        fields: {
+          before: {select: 'left.captures[0].version'},
+          file: '{{filePath}}',
+          missing: {select: 'right.captures[3].name'},
+          summary: '{{concernId}}: <{{right.artifact}}>',
+          versions: {select: 'right.captures[*].version'},
+        },
+        type: 'json',
+      }
+
+      const output = executeReport(report, filterResult, {concernId: 'deps', filePath: 'Cargo.toml'})
+
+      const expected = {
+        before: '1.0.100',
+        file: 'Cargo.toml',
+        summary: 'deps: <serde = "1.0.200">',
+        versions: ['1.0.200'],
+      }
+      expect(output.metadata?.data).to.deep.equal(expected)
+      expect(JSON.parse(output.content)).to.deep.equal(expected)
+      expect(output.metadata?.message).to.equal(output.content)
+    })
+
+    it('validates the object against its schema', () => {
+      const report: JsonReport = {
+        fields: {version: {select: 'right.captures[0].version'}},
+        schema: {
+          properties: {version: {pattern: '^2\\.', type: 'string'}},
+          required: ['version', 'name'],
+          type: 'object',
+        },
+        type: 'json',
+      }
+
+      try {
+        executeReport(report, filterResult, {filePath: 'Cargo.toml'})
+        expect.fail('Should have thrown')
+      } catch (error) {
+        expect((error as Error).message).to.include('Report output does not match its schema')
+        expect((error as Error).message).to.include("must have required property 'name'")
+        expect((error as Error).message).to.include('output/version must match pattern')
+      }
+    })
+
+    it('reports invalid selectors and schemas with their path', () => {
+      const invalidSelector: JsonReport = {fields: {name: {select: 'right.captures['}}, type: 'json'}
+      expect(() => validateReport(invalidSelector))
+        .to.throw('Invalid selector')
+        .with.property('path')
+        .that.deep.equals(['fields', 'name', 'select'])
+
+      const invalidSchema: JsonReport = {fields: {}, schema: {type: 'objekt'}, type: 'json'}
+      expect(() => validateReport(invalidSchema))
+        .to.throw('Invalid JSON Schema')
+        .with.property('path')
+        .that.deep.equals(['schema'])
+    })
   })
 })
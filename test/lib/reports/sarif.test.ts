@@ -0,0 +1,142 @@
+import {expect} from 'chai'
+
+import type {FilterResult} from '../../../src/lib/configuration/config.js'
+
+import {executeReport, type ReportOutput, type SarifReport} from '../../../src/lib/reports/index.js'
+import {createSarifLog} from '../../../src/lib/reports/sarif.js'
+
+const tool = {informationUri: 'https://github.com/zetlen/distill', name: 'distill', version: '3.0.0'}
+
+describe('reports/sarif', () => {
+  describe('sarif report', () => {
+    it('renders the message and its markdown variant', () => {
+      const report: SarifReport = {
+        markdown: '`{{filePath}}` changed',
+        template: '{{filePath}} changed',
+        type: 'sarif',
+      }
+      const filterResult: FilterResult = {diffText: 'diff', left: {artifact: 'a'}, right: {artifact: 'b'}}
+
+      const output = executeReport(report, filterResult, {concernId: 'security', filePath: 'src/auth.ts'})
+
+      expect(output.content).to.equal('src/auth.ts changed')
+      expect(output.metadata).to.deep.include({
+        concernId: 'security',
+        markdown: '`src/auth.ts` changed',
+        message: 'src/auth.ts changed',
+      })
+    })
+  })
+
+  describe('createSarifLog', () => {
+    it('maps concerns to rules and signals to results with source locations', () => {
+      const reports: ReportOutput[] = [
+        {
+          content: 'Secret added',
+          metadata: {
+            concernId: 'security',
+            diffText: '',
+            fileName: 'src/config.ts',
+            locations: {
+              new: [{endColumn: 20, endLine: 3, path: 'src/config.ts', startColumn: 5, startLine: 3}],
+              old: [{endColumn: 12, endLine: 2, path: 'src/config.ts', startColumn: 1, startLine: 2}],
+            },
+            message: 'Secret added',
+            severity: 'error',
+            signalId: 'secrets',
+            stakeholders: [{contactMethod: 'github-reviewer-request', name: 'Security Team'}],
+            watchType: 'regex',
+          },
+        },
+        {
+          content: 'Dependencies changed',
+          metadata: {
+            concernId: 'deps',
+            diffText: '',
+            fileName: 'package.json',
+            message: 'Dependencies changed',
+            signalId: '0',
+            watchType: 'jq',
+          },
+        },
+        {
+          content: 'Another secret',
+          metadata: {concernId: 'security', diffText: '', fileName: 'src/env.ts', message: 'Another secret'},
+        },
+      ]
+
+      const log = createSarifLog(reports, tool)
+
+      expect(log.version).to.equal('2.1.0')
+      expect(log.$schema).to.contain('sarif-2.1.0')
+      expect(log.runs).to.have.length(1)
+
+      const [run] = log.runs
+      expect(run.tool.driver).to.deep.include({informationUri: tool.informationUri, name: 'distill', version: '3.0.0'})
+      expect(run.tool.driver.rules.map((rule) => rule.id)).to.deep.equal(['security', 'deps'])
+      expect(run.tool.driver.rules[0].properties).to.deep.equal({stakeholders: ['Security Team']})
+
+      expect(run.results.map((result) => [result.ruleId, result.ruleIndex])).to.deep.equal([
+        ['security', 0],
+        ['deps', 1],
+        ['security', 0],
+      ])
+
+      const [secret, deps] = run.results
+      expect(run.results.map((result) => result.level)).to.deep.equal(['error', 'note', 'note'])
+      expect(secret.message).to.deep.equal({text: 'Secret added'})
+      expect(secret.properties).to.deep.equal({signalId: 'secrets', watchType: 'regex'})
+      expect(secret.locations).to.deep.equal([
+        {
+          physicalLocation: {
+            artifactLocation: {uri: 'src/config.ts'},
+            region: {endColumn: 20, endLine: 3, startColumn: 5, startLine: 3},
+          },
+        },
+      ])
+      expect(secret.relatedLocations?.[0].physicalLocation.region?.startLine).to.equal(2)
+
+      // Watches without source locations point at the file
+      expect(deps.locations).to.deep.equal([{physicalLocation: {artifactLocation: {uri: 'package.json'}}}])
+      expect(deps.relatedLocations).to.be.undefined
+    })
+
+    it('gives results stable fingerprints', () => {
+      const report: ReportOutput = {
+        content: 'x',
+        metadata: {concernId: 'a', diffText: 'one', fileName: 'f.ts', message: 'x'},
+      }
+      const moved: ReportOutput = {
+        content: 'x',
+        metadata: {concernId: 'a', diffText: 'two', fileName: 'f.ts', message: 'x'},
+      }
+      const other: ReportOutput = {
+        content: 'y',
+        metadata: {concernId: 'a', diffText: 'one', fileName: 'f.ts', message: 'y'},
+      }
+
+      const [first, second, third] = createSarifLog([report, moved, other], tool).runs[0].results
+
+      expect(first.partialFingerprints).to.deep.equal(second.partialFingerprints)
+      expect(first.partialFingerprints).to.not.deep.equal(third.partialFingerprints)
+    })
+
+    it('falls back to a default rule and escapes paths', () => {
+      const log = createSarifLog(
+        [{content: 'note', metadata: {diffText: '', fileName: 'docs/my file.md', message: 'note'}}],
+        {name: 'distill'},
+      )
+
+      const [run] = log.runs
+      expect(run.tool.driver.rules.map((rule) => rule.id)).to.deep.equal(['distill'])
+      expect(run.tool.driver).to.not.have.property('version')
+      expect(run.results[0].locations[0].physicalLocation.artifactLocation.uri).to.equal('docs/my%20file.md')
+    })
+
+    it('produces an empty run when nothing fired', () => {
+      const log = createSarifLog([], tool)
+      expect(log.runs[0].results).to.be.empty
+      expect(log.runs[0].tool.driver.rules).to.be.empty
+    })
+  })
+})
@@ -0,0 +1,41 @@
+import {expect} from 'chai'
+
+import {parseSelector, select} from '../../../src/lib/reports/selector.js'
+
+describe('reports/selector', () => {
+  const data = {
+    filePath: 'Cargo.toml',
+    'odd key': true,
+    right: {
+      captures: [
+        {name: 'serde', version: '1.0.200'},
+        {name: 'tokio', version: '1.37.0'},
+      ],
+    },
+  }
+
+  it('selects nested values', () => {
+    expect(select(data, 'filePath')).to.equal('Cargo.toml')
+    expect(select(data, '$.right.captures[1].name')).to.equal('tokio')
+    expect(select(data, 'right.captures[-1].version')).to.equal('1.37.0')
+    expect(select(data, "['odd key']")).to.equal(true)
+    expect(select(data, '$')).to.equal(data)
+  })
+
+  it('collects values under wildcards into an array', () => {
+    expect(select(data, 'right.captures[*].version')).to.deep.equal(['1.0.200', '1.37.0'])
+    expect(select(data, 'right.captures[0].*')).to.deep.equal(['serde', '1.0.200'])
+    expect(select(data, 'left.captures[*].version')).to.deep.equal([])
+  })
+
+  it('returns undefined for missing paths', () => {
+    expect(select(data, 'right.captures[5].name')).to.be.undefined
+    expect(select(data, 'filePath.length')).to.be.undefined
+    expect(select(data, 'constructor')).to.be.undefined
+  })
+
+  it('rejects malformed selectors', () => {
+    expect(() => parseSelector('right.captures[')).to.throw('Invalid selector "right.captures[" at "["')
+    expect(() => parseSelector('right..name')).to.throw('Invalid selector')
+  })
+})
@@ -68,6 +68,27 @@ describe('applyWatch (orchestrator)', () => {
     expect(result!.right.artifact).to.include('function validateEmail')
   })
 
+  it('routes to toml watch with WatchExtractionConfig', async () => {
+    const versions = await fixtures.toml.cargo()
+    const config: WatchExtractionConfig = {query: '.package.version', type: 'toml'}
+
+    const result = await applyWatch(config, versions, 'Cargo.toml')
+
+    expect(result).to.not.be.null
+    expect(result!.right.artifact.trim()).to.equal('"0.4.0"')
+  })
+
+  it('routes to yaml watch with WatchExtractionConfig', async () => {
+    const versions = await fixtures.yaml.deployment()
+    const config: WatchExtractionConfig = {query: 'select(.kind == "Deployment") | .spec.replicas', type: 'yaml'}
+
+    const result = await applyWatch(config, versions, 'deployment.yaml')
+
+    expect(result).to.not.be.null
+    expect(result!.left.artifact.trim()).to.equal('2')
+    expect(result!.right.artifact.trim()).to.equal('4')
+  })
+
   it('throws for unsupported watch type', async () => {
     const versions = {
       newContent: 'test2',
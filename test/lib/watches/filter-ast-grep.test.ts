@@ -237,7 +237,152 @@ describe('astGrepFilter', () => {
     })
   })
 
+  describe('pattern objects', () => {
+    it('matches contexts containing quotes and newlines', async () => {
+      const versions = {
+        newContent: 'class Cmd {\n  static description = "Run the other thing"\n}\n',
+        oldContent: 'class Cmd {\n  static description = "Run the thing"\n}\n',
+      }
+
+      const result = await astGrepFilter.apply(versions, {
+        language: 'typescript',
+        pattern: {
+          context: 'class C {\n  static description = "$DESCRIPTION"\n}',
+          selector: 'public_field_definition',
+        },
+        type: 'ast-grep',
+      })
+
+      expect(result).to.not.be.null
+      expect(result!.left.artifact).to.equal('static description = "Run the thing"')
+      expect(result!.right.artifact).to.equal('static description = "Run the other thing"')
+    })
+  })
+
+  describe('metavariables', () => {
+    it('captures single and multiple metavariables as context', async () => {
+      const versions = {
+        newContent: 'fetch(url, {method: "POST"})',
+        oldContent: 'fetch(url)',
+      }
+
+      const result = await astGrepFilter.apply(versions, {
+        language: 'js',
+        pattern: '$FN($$$ARGS)',
+        type: 'ast-grep',
+      })
+
+      expect(result).to.not.be.null
+      expect(result!.context).to.deep.include([{ARGS: 'url, {method: "POST"}', FN: 'fetch'}])
+      expect(result!.left.captures).to.deep.equal([{ARGS: 'url', FN: 'fetch'}])
+      expect(result!.right.captures).to.deep.equal([{ARGS: 'url, {method: "POST"}', FN: 'fetch'}])
+    })
+
+    it('captures transformed metavariables', async () => {
+      const versions = {
+        newContent: 'const user_name = 1',
+        oldContent: '',
+      }
+
+      const result = await astGrepFilter.apply(versions, {
+        language: 'javascript',
+        pattern: 'const $NAME = $VALUE',
+        transform: {CAMEL: {convert: {source: '$NAME', toCase: 'camelCase'}}},
+        type: 'ast-grep',
+      })
+
+      expect(result!.right.captures).to.deep.equal([{CAMEL: 'userName', NAME: 'user_name', VALUE: '1'}])
+    })
+  })
+
+  describe('rules', () => {
+    const source = [
+      'async function load() {',
+      '  try {',
+      "    await fetch('/safe')",
+      '  } catch {}',
+      "  await fetch('/unsafe')",
+      '}',
+    ].join('\n')
+
+    it('matches relational and composite rules', async () => {
+      const result = await astGrepFilter.apply(
+        {newContent: source, oldContent: null},
+        {
+          language: 'javascript',
+          rule: {not: {inside: {kind: 'try_statement', stopBy: 'end'}}, pattern: 'fetch($$$ARGS)'},
+          type: 'ast-grep',
+        },
+      )
+
+      expect(result).to.not.be.null
+      expect(result!.right.artifact).to.equal("fetch('/unsafe')")
+      expect(result!.right.captures).to.deep.equal([{ARGS: "'/unsafe'"}])
+    })
+
+    it('applies constraints to metavariables', async () => {
+      const result = await astGrepFilter.apply(
+        {newContent: source, oldContent: null},
+        {
+          constraints: {URL: {regex: 'unsafe'}},
+          language: 'javascript',
+          rule: {any: [{pattern: 'fetch($URL)'}, {pattern: 'axios.get($URL)'}]},
+          type: 'ast-grep',
+        },
+      )
+
+      expect(result!.right.artifact).to.equal("fetch('/unsafe')")
+    })
+
+    it('resolves utility rules', async () => {
+      const result = await astGrepFilter.apply(
+        {newContent: source, oldContent: null},
+        {
+          language: 'javascript',
+          rule: {all: [{kind: 'call_expression'}, {matches: 'in-try'}]},
+          type: 'ast-grep',
+          utils: {'in-try': {inside: {kind: 'try_statement', stopBy: 'end'}}},
+        },
+      )
+
+      expect(result!.right.artifact).to.equal("fetch('/safe')")
+    })
+
+    it('matches what both a pattern and a rule match', async () => {
+      const result = await astGrepFilter.apply(
+        {newContent: source, oldContent: null},
+        {
+          language: 'javascript',
+          pattern: 'fetch($URL)',
+          rule: {inside: {kind: 'try_statement', stopBy: 'end'}},
+          type: 'ast-grep',
+        },
+      )
+
+      expect(result!.right.artifact).to.equal("fetch('/safe')")
+      expect(result!.right.captures).to.deep.equal([{URL: "'/safe'"}])
+    })
+
+    it('requires a pattern or a rule', async () => {
+      try {
+        await astGrepFilter.validate({language: 'javascript', type: 'ast-grep'})
+        expect.fail('Should have thrown')
+      } catch (error) {
+        expect((error as Error).message).to.equal('ast-grep watch requires a pattern or a rule')
+      }
+    })
+  })
+
   describe('error handling', () => {
+    it('rejects unsupported languages', async () => {
+      try {
+        await astGrepFilter.validate({language: 'cobol', pattern: 'MOVE $A TO $B', type: 'ast-grep'})
+        expect.fail('Should have thrown')
+      } catch (error) {
+        expect((error as Error).message).to.include('Unsupported ast-grep language "cobol"')
+      }
+    })
+
     it('returns null when matches are identical', async () => {
       const versions = {
         newContent: 'function same() { }\nconst x = different;',
@@ -83,4 +83,65 @@ describe('jqFilter', () => {
 
     expect(result).to.be.null
   })
+
+  it('locates the values a path expression selects', async () => {
+    const versions = await fixtures.jq.package()
+
+    const result = await jqFilter.apply(versions, {query: '.dependencies.express', type: 'jq'})
+
+    const range = {endColumn: 24, endLine: 7, startColumn: 16, startLine: 7}
+    expect(result!.left.matches).to.deep.equal([{range, text: '"4.17.1"'}])
+    expect(result!.right.matches).to.deep.equal([{range, text: '"4.18.2"'}])
+  })
+
+  it('leaves values computed by the query unlocated', async () => {
+    const versions = await fixtures.jq.package()
+
+    const result = await jqFilter.apply(versions, {query: '.dependencies | keys', type: 'jq'})
+
+    expect(result).to.not.be.null
+    expect(result!.left.matches).to.be.undefined
+    expect(result!.right.matches).to.be.undefined
+  })
+
+  it('processes a stream of JSON values', async () => {
+    const versions = {newContent: '{"a": 1}\n{"a": 2}\n', oldContent: '{"a": 1}\n'}
+
+    const result = await jqFilter.apply(versions, {query: '.a', type: 'jq'})
+
+    expect(result!.right.artifact).to.equal('1\n2\n')
+  })
+
+  it('throws on invalid JSON instead of returning partial output', async () => {
+    const versions = {newContent: '{"name": "test"}\n{"name": ', oldContent: null}
+
+    try {
+      await jqFilter.apply(versions, {query: '.name', type: 'jq'})
+      expect.fail('Should have thrown')
+    } catch (error) {
+      expect((error as Error).message).to.match(/^jq: error/)
+    }
+  })
+
+  it('throws on runtime errors', async () => {
+    const versions = {newContent: '"just a string"', oldContent: null}
+
+    try {
+      await jqFilter.apply(versions, {query: '.name', type: 'jq'})
+      expect.fail('Should have thrown')
+    } catch (error) {
+      expect((error as Error).message).to.include('Cannot index string')
+    }
+  })
+
+  it('validates queries without running them', async () => {
+    await jqFilter.validate({query: '.dependencies | keys # names only', type: 'jq'})
+
+    try {
+      await jqFilter.validate({query: '.dependencies |', type: 'jq'})
+      expect.fail('Should have thrown')
+    } catch (error) {
+      expect((error as Error).message).to.include('compile error')
+    }
+  })
 })
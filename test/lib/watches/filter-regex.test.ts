@@ -69,4 +69,21 @@ describe('regexFilter', () => {
     expect(result!.left.artifact).to.equal('')
     expect(result!.right.artifact).to.include('secret123')
   })
+
+  it('records where each match is in the source', async () => {
+    const versions = {
+      newContent: 'name=app\nport=8080\nhost=localhost\nport=9090\n',
+      oldContent: 'port=80\n',
+    }
+
+    const result = await regexFilter.apply(versions, {pattern: '^port=\\d+$', type: 'regex'})
+
+    expect(result!.left.matches).to.deep.equal([
+      {range: {endColumn: 8, endLine: 1, startColumn: 1, startLine: 1}, text: 'port=80'},
+    ])
+    expect(result!.right.matches).to.deep.equal([
+      {range: {endColumn: 10, endLine: 2, startColumn: 1, startLine: 2}, text: 'port=8080'},
+      {range: {endColumn: 10, endLine: 4, startColumn: 1, startLine: 4}, text: 'port=9090'},
+    ])
+  })
 })
@@ -0,0 +1,88 @@
+import {expect} from 'chai'
+
+import {tomlFilter} from '../../../src/lib/watches/index.js'
+import {fixtures} from '../../fixtures/loader.js'
+
+describe('tomlFilter', () => {
+  it('returns null when only table style, array wrapping and comments change', async () => {
+    const versions = await fixtures.toml.cargo()
+
+    const result = await tomlFilter.apply(versions, {query: '.dependencies', type: 'toml'})
+
+    expect(result).to.be.null
+  })
+
+  it('reports changed values', async () => {
+    const versions = await fixtures.toml.cargo()
+
+    const result = await tomlFilter.apply(versions, {query: '.["dev-dependencies"]', type: 'toml'})
+
+    expect(result).to.not.be.null
+    expect(result!.diffText).to.include('-  "insta": "1.39"')
+    expect(result!.diffText).to.include('+  "insta": "1.40"')
+  })
+
+  it('extracts scalar values', async () => {
+    const versions = await fixtures.toml.cargo()
+
+    const result = await tomlFilter.apply(versions, {query: '.package.version', type: 'toml'})
+
+    expect(result).to.not.be.null
+    expect(result!.left.artifact.trim()).to.equal('"0.3.0"')
+    expect(result!.right.artifact.trim()).to.equal('"0.4.0"')
+  })
+
+  it('locates the keys a path expression selects', async () => {
+    const versions = await fixtures.toml.cargo()
+
+    const result = await tomlFilter.apply(versions, {query: '.package.version', type: 'toml'})
+
+    const range = {endColumn: 18, endLine: 3, startColumn: 1, startLine: 3}
+    expect(result!.left.matches).to.deep.equal([{range, text: '"0.3.0"'}])
+    expect(result!.right.matches).to.deep.equal([{range, text: '"0.4.0"'}])
+  })
+
+  it('serializes dates as strings', async () => {
+    const versions = {newContent: 'released = 2024-05-01\n', oldContent: null}
+
+    const result = await tomlFilter.apply(versions, {query: '.released | type', type: 'toml'})
+
+    expect(result!.right.artifact.trim()).to.equal('"string"')
+  })
+
+  it('handles null old content (new file)', async () => {
+    const versions = {newContent: 'name = "test"\n', oldContent: null}
+
+    const result = await tomlFilter.apply(versions, {query: '.name', type: 'toml'})
+
+    expect(result).to.not.be.null
+    expect(result!.left.artifact).to.equal('')
+    expect(result!.right.artifact.trim()).to.equal('"test"')
+  })
+
+  it('treats an empty file like a missing one', async () => {
+    const versions = {newContent: 'name = "test"\n', oldContent: ''}
+
+    const result = await tomlFilter.apply(versions, {query: '.name', type: 'toml'})
+
+    expect(result!.left.artifact).to.equal('')
+    expect(result!.right.artifact.trim()).to.equal('"test"')
+  })
+
+  it('throws on invalid TOML', async () => {
+    const versions = {newContent: '[package\nname = "test"\n', oldContent: null}
+
+    try {
+      await tomlFilter.apply(versions, {query: '.', type: 'toml'})
+      expect.fail('Should have thrown')
+    } catch (error) {
+      expect((error as Error).message).to.include('Invalid TOML')
+    }
+  })
+
+  it('returns null when both contents are null', async () => {
+    const result = await tomlFilter.apply({newContent: null, oldContent: null}, {query: '.', type: 'toml'})
+
+    expect(result).to.be.null
+  })
+})
@@ -431,4 +431,22 @@ describe('tsqFilter', () => {
       }
     })
   })
+
+  describe('source locations', () => {
+    it('records where each captured node is in the source', async () => {
+      const versions = {
+        newContent: 'const a = 1\n\nfunction b() {\n  return a\n}\n',
+        oldContent: null,
+      }
+
+      const result = await tsqFilter.apply(versions, {query: '(function_declaration) @fn', type: 'tsq'}, 'b.js')
+
+      expect(result!.right.matches).to.deep.equal([
+        {
+          range: {endColumn: 2, endLine: 5, startColumn: 1, startLine: 3},
+          text: 'function b() {\n  return a\n}',
+        },
+      ])
+    })
+  })
 })
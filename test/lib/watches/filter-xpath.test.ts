@@ -66,4 +66,18 @@ describe('xpathFilter', () => {
     expect(result!.left.artifact).to.equal('')
     expect(result!.right.artifact).to.include('value')
   })
+
+  it('records where selected elements are in the source', async () => {
+    const versions = {
+      newContent: '<root>\n  <item>a</item>\n  <item>\n    <item>b</item>\n  </item>\n</root>\n',
+      oldContent: '<root/>',
+    }
+
+    const result = await xpathFilter.apply(versions, {expression: '/root/item', type: 'xpath'})
+
+    expect(result!.right.matches!.map((match) => match.range)).to.deep.equal([
+      {endColumn: 17, endLine: 2, startColumn: 3, startLine: 2},
+      {endColumn: 10, endLine: 5, startColumn: 3, startLine: 3},
+    ])
+  })
 })
@@ -0,0 +1,76 @@
+import {expect} from 'chai'
+
+import {yamlFilter} from '../../../src/lib/watches/index.js'
+import {fixtures} from '../../fixtures/loader.js'
+
+describe('yamlFilter', () => {
+  it('extracts values from a multi-document file', async () => {
+    const versions = await fixtures.yaml.deployment()
+
+    const result = await yamlFilter.apply(versions, {
+      query: 'select(.kind == "Deployment") | .spec.template.spec.containers[].image',
+      type: 'yaml',
+    })
+
+    expect(result).to.not.be.null
+    expect(result!.left.artifact.trim()).to.equal('"registry.example.com/web:1.4.0"')
+    expect(result!.right.artifact.trim()).to.equal('"registry.example.com/web:1.5.0"')
+    expect(result!.diffText).to.include('+"registry.example.com/web:1.5.0"')
+  })
+
+  it('returns null when only key order, quoting and comments change', async () => {
+    const versions = await fixtures.yaml.deployment()
+
+    const result = await yamlFilter.apply(versions, {query: 'select(.kind == "Service")', type: 'yaml'})
+
+    expect(result).to.be.null
+  })
+
+  it('reports changed values', async () => {
+    const versions = await fixtures.yaml.deployment()
+
+    const result = await yamlFilter.apply(versions, {query: 'select(.kind == "Deployment") | .spec', type: 'yaml'})
+
+    expect(result).to.not.be.null
+    expect(result!.diffText).to.include('-  "replicas": 2')
+    expect(result!.diffText).to.include('+  "replicas": 4')
+  })
+
+  it('locates the values a path expression selects', async () => {
+    const versions = await fixtures.yaml.deployment()
+
+    const query = 'select(.kind == "Deployment") | .spec.replicas'
+    const result = await yamlFilter.apply(versions, {query, type: 'yaml'})
+
+    const range = {endColumn: 14, endLine: 7, startColumn: 13, startLine: 7}
+    expect(result!.left.matches).to.deep.equal([{range, text: '2'}])
+    expect(result!.right.matches).to.deep.equal([{range, text: '4'}])
+  })
+
+  it('handles null old content (new file)', async () => {
+    const versions = {newContent: 'name: test\n', oldContent: null}
+
+    const result = await yamlFilter.apply(versions, {query: '.name', type: 'yaml'})
+
+    expect(result).to.not.be.null
+    expect(result!.left.artifact).to.equal('')
+    expect(result!.right.artifact.trim()).to.equal('"test"')
+  })
+
+  it('throws on invalid YAML', async () => {
+    const versions = {newContent: 'key: [unclosed\n', oldContent: null}
+
+    try {
+      await yamlFilter.apply(versions, {query: '.', type: 'yaml'})
+      expect.fail('Should have thrown')
+    } catch (error) {
+      expect((error as Error).message).to.include('Invalid YAML')
+    }
+  })
+
+  it('returns null when both contents are null', async () => {
+    const result = await yamlFilter.apply({newContent: null, oldContent: null}, {query: '.', type: 'yaml'})
+
+    expect(result).to.be.null
+  })
+})
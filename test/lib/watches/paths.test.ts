@@ -0,0 +1,66 @@
+import {expect} from 'chai'
+
+import {createTomlPathLocator, createYamlPathLocator} from '../../../src/lib/watches/paths.js'
+
+describe('watch paths', () => {
+  describe('createTomlPathLocator', () => {
+    const locate = createTomlPathLocator(
+      [
+        '[package]',
+        'name = "widget"',
+        '',
+        '[dependencies]',
+        'tokio = { version = "1.38", features = [',
+        '  "macros",',
+        '] }',
+        '',
+        '[dependencies.serde]',
+        'version = "1.0"',
+        '',
+        '[[bin]]',
+        'name = "a"',
+        '',
+        '[[bin]]',
+        'name = "b"',
+        '',
+      ].join('\n'),
+    )
+
+    it('locates keys, including values continued over several lines', () => {
+      expect(locate(['package', 'name'], 0)).to.deep.equal({endColumn: 16, endLine: 2, startColumn: 1, startLine: 2})
+      expect(locate(['dependencies', 'tokio', 'features', 0], 0)).to.deep.equal({
+        endColumn: 4,
+        endLine: 7,
+        startColumn: 1,
+        startLine: 5,
+      })
+    })
+
+    it('locates tables from their header to their last key', () => {
+      expect(locate(['dependencies'], 0)).to.deep.equal({endColumn: 4, endLine: 7, startColumn: 1, startLine: 4})
+      expect(locate(['dependencies', 'serde'], 0)).to.deep.equal({
+        endColumn: 16,
+        endLine: 10,
+        startColumn: 1,
+        startLine: 9,
+      })
+    })
+
+    it('counts arrays of tables', () => {
+      expect(locate(['bin', 1, 'name'], 0)).to.deep.equal({endColumn: 11, endLine: 16, startColumn: 1, startLine: 16})
+      expect(locate(['missing'], 0)).to.be.undefined
+    })
+  })
+
+  describe('createYamlPathLocator', () => {
+    it('skips empty documents like the yaml filter', () => {
+      const locate = createYamlPathLocator('---\n---\nimage: web:1.5.0\n')
+
+      expect(locate!(['image'], 0)).to.deep.equal({endColumn: 17, endLine: 3, startColumn: 8, startLine: 3})
+    })
+
+    it('cannot locate content that does not parse', () => {
+      expect(createYamlPathLocator('key: [unclosed\n')).to.be.undefined
+    })
+  })
+})